@@ -1,4 +1,15 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::{spl_token_2022::instruction::AuthorityType, Token2022};
+use anchor_spl::token_interface;
+
+pub mod math;
+pub mod multisig;
+pub mod nft;
+pub mod oracle;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
@@ -7,12 +18,18 @@ pub mod solana_contracts {
     use super::*;
 
     // Constants
-    const LTV_BP: u16 = 6000; // 60% LTV
-    const APY_BP: u16 = 1800; // 18% APY
-    const BOOST_APY_BP: u16 = 500; // 5% boost APY
-    const BOOST_TARGET_BP: u16 = 3000; // 30% of principal for max boost
+    const DEFAULT_LTV_BP: u16 = 6000; // 60% LTV
+    const DEFAULT_APY_TIERS_BP: [u16; 4] = [1800, 2400, 3000, 3600]; // 18/24/30/36% base APY tiers
     const SECONDS_PER_DAY: u64 = 86400;
     const SECONDS_PER_MONTH: u64 = 30 * SECONDS_PER_DAY;
+    const MIN_LOCK_PERIOD_MONTHS: u8 = 12;
+    const MAX_LOCK_PERIOD_MONTHS: u8 = 36;
+    const MAX_LOCK_TERMS: usize = 8;
+    const MAX_MARKETPLACE_FEE_BP: u16 = 1000; // 10%
+    const MAX_BORROW_RATE_BP: u16 = 5000; // 50% a year
+    const MAX_ACCEPTED_MINTS: usize = 8;
+    const DEFAULT_COLLATERAL_USER_SHARE_BP: u16 = 4000; // 40% of yield to the holder while pledged
+    const MAX_PARAM_CHANGE_DELAY: i64 = 30 * SECONDS_PER_DAY as i64;
 
     // Error codes
     #[error_code]
@@ -49,13 +66,259 @@ pub mod solana_contracts {
         ReentrancyDetected,
         #[msg("Too early to accrue rewards")]
         TooEarlyToAccrue,
+        #[msg("Invalid mint")]
+        InvalidMint,
+        #[msg("Insufficient vault balance")]
+        InsufficientVaultBalance,
+        #[msg("Invalid vault")]
+        InvalidVault,
+        #[msg("Insufficient pool liquidity")]
+        InsufficientLiquidity,
+        #[msg("Invalid APY")]
+        InvalidApy,
+        #[msg("Invalid lock period")]
+        InvalidLockPeriod,
+        #[msg("Invalid deposit limits")]
+        InvalidDepositLimits,
+        #[msg("Pool is not active")]
+        PoolInactive,
+        #[msg("Deposit below pool minimum")]
+        DepositTooSmall,
+        #[msg("Deposit above pool maximum")]
+        DepositTooLarge,
+        #[msg("Nothing left to accrue")]
+        NothingToAccrue,
+        #[msg("Boost token is not active")]
+        BoostTokenInactive,
+        #[msg("Invalid price account")]
+        InvalidPriceAccount,
+        #[msg("No valid price available")]
+        NoValidPrice,
+        #[msg("Invalid price oracle configuration")]
+        InvalidOracleConfig,
+        #[msg("Wexel is locked")]
+        WexelLocked,
+        #[msg("Invalid recipient")]
+        InvalidRecipient,
+        #[msg("Invalid price")]
+        InvalidPrice,
+        #[msg("Fee too high")]
+        FeeTooHigh,
+        #[msg("Invalid treasury account")]
+        InvalidTreasury,
+        #[msg("Offer has expired")]
+        OfferExpired,
+        #[msg("Offer has not expired")]
+        OfferNotExpired,
+        #[msg("Wexel does not match the offer")]
+        OfferCriteriaNotMet,
+        #[msg("Invalid expiry")]
+        InvalidExpiry,
+        #[msg("Invalid auction parameters")]
+        InvalidAuction,
+        #[msg("Auction has not started")]
+        AuctionNotStarted,
+        #[msg("Price exceeds the buyer's maximum")]
+        SlippageExceeded,
+        #[msg("Invalid yield split")]
+        InvalidYieldSplit,
+        #[msg("Invalid borrow rate")]
+        InvalidBorrowRate,
+        #[msg("Operation is paused")]
+        OperationPaused,
+        #[msg("Mint is not accepted")]
+        MintNotAccepted,
+        #[msg("Too many accepted mints")]
+        TooManyAcceptedMints,
+        #[msg("Invalid authority")]
+        InvalidAuthority,
+        #[msg("No pending authority proposal")]
+        NoPendingAuthority,
+        #[msg("Invalid parameter value")]
+        InvalidParameter,
+        #[msg("Invalid timelock delay")]
+        InvalidDelay,
+        #[msg("ETA is earlier than the timelock delay allows")]
+        EtaTooEarly,
+        #[msg("Parameter change is not ready")]
+        ParamChangeNotReady,
+        #[msg("Parameter change is already due")]
+        ParamChangeDue,
+        #[msg("Invalid multisig signers or threshold")]
+        InvalidMultisig,
+        #[msg("Signer is not part of the multisig")]
+        NotMultisigSigner,
+        #[msg("Proposal already approved by this signer")]
+        AlreadyApproved,
+        #[msg("Proposal has not reached the approval threshold")]
+        ThresholdNotReached,
+        #[msg("Proposal predates the current signer set")]
+        StaleProposal,
+        #[msg("Invalid instruction for the multisig to invoke")]
+        InvalidInvoke,
+        #[msg("Every pinned price source must be passed")]
+        MissingPriceSource,
     }
 
     // Events
+    #[event]
+    pub struct ConfigInitialized {
+        pub admin: Pubkey,
+        pub treasury: Pubkey,
+        pub accepted_mints: Vec<Pubkey>,
+        pub initialized_at: i64,
+    }
+
+    #[event]
+    pub struct PauseFlagsChanged {
+        pub paused: PauseFlags,
+        pub changed_by: Pubkey,
+    }
+
+    #[event]
+    pub struct RoleGranted {
+        pub role: Role,
+        pub holder: Pubkey,
+        pub granted_by: Pubkey,
+    }
+
+    #[event]
+    pub struct RoleRevoked {
+        pub role: Role,
+        pub holder: Pubkey,
+        pub revoked_by: Pubkey,
+    }
+
+    #[event]
+    pub struct MultisigCreated {
+        pub signers: Vec<Pubkey>,
+        pub threshold: u8,
+        pub created_by: Pubkey,
+    }
+
+    #[event]
+    pub struct MultisigSignersChanged {
+        pub signers: Vec<Pubkey>,
+        pub threshold: u8,
+        pub signer_set_seqno: u32,
+    }
+
+    #[event]
+    pub struct ProposalCreated {
+        pub proposal_id: u64,
+        pub proposer: Pubkey,
+        pub action: MultisigAction,
+    }
+
+    #[event]
+    pub struct ProposalApproved {
+        pub proposal_id: u64,
+        pub signer: Pubkey,
+        pub approvals: u8,
+    }
+
+    #[event]
+    pub struct ProposalExecuted {
+        pub proposal_id: u64,
+        pub executed_by: Pubkey,
+    }
+
+    #[event]
+    pub struct ProposalCancelled {
+        pub proposal_id: u64,
+        pub cancelled_by: Pubkey,
+    }
+
+    #[event]
+    pub struct ParamChangeScheduled {
+        pub change_id: u64,
+        pub change: ParamChange,
+        pub eta: i64,
+        pub scheduled_by: Pubkey,
+    }
+
+    #[event]
+    pub struct ParamChangeExecuted {
+        pub change_id: u64,
+        pub change: ParamChange,
+        pub executed_by: Pubkey,
+        pub executed_at: i64,
+    }
+
+    #[event]
+    pub struct ParamChangeCancelled {
+        pub change_id: u64,
+        pub change: ParamChange,
+        pub cancelled_by: Pubkey,
+    }
+
+    #[event]
+    pub struct AuthorityProposed {
+        pub role: AuthorityRole,
+        pub current: Pubkey,
+        pub proposed: Pubkey,
+        pub proposed_by: Pubkey,
+    }
+
+    #[event]
+    pub struct AuthorityAccepted {
+        pub role: AuthorityRole,
+        pub old_authority: Pubkey,
+        pub new_authority: Pubkey,
+    }
+
+    #[event]
+    pub struct AuthorityProposalCancelled {
+        pub role: AuthorityRole,
+        pub cancelled: Pubkey,
+        pub cancelled_by: Pubkey,
+    }
+
+    #[event]
+    pub struct AcceptedMintsUpdated {
+        pub accepted_mints: Vec<Pubkey>,
+        pub updated_by: Pubkey,
+    }
+
+    #[event]
+    pub struct PoolCreated {
+        pub pool_id: u64,
+        pub usdt_mint: Pubkey,
+        pub lock_terms: Vec<LockTerm>,
+        pub min_deposit_usd: u64,
+        pub max_deposit_usd: u64,
+        pub borrow_rate_bp: u16,
+        pub created_at: i64,
+    }
+
+    #[event]
+    pub struct PoolStatusChanged {
+        pub pool_id: u64,
+        pub is_active: bool,
+        pub changed_by: Pubkey,
+    }
+
+    #[event]
+    pub struct RewardsVaultInitialized {
+        pub pool_id: u64,
+        pub rewards_vault: Pubkey,
+        pub token_vault: Pubkey,
+    }
+
+    #[event]
+    pub struct RewardsVaultFunded {
+        pub pool_id: u64,
+        pub amount: u64,
+        pub total_funded: u64,
+        pub funded_by: Pubkey,
+    }
+
     #[event]
     pub struct WexelCreated {
         pub id: u64,
+        pub pool_id: u64,
         pub owner: Pubkey,
+        pub nft_mint: Pubkey,
         pub principal_usd: u64,
         pub apy_bp: u16,
         pub lock_period_months: u8,
@@ -67,12 +330,43 @@ pub mod solana_contracts {
         pub wexel_id: u64,
         pub apy_boost_bp: u16,
         pub value_usd: u64,
+        pub boost_mint: Pubkey,
+        pub amount: u64,
+    }
+
+    #[event]
+    pub struct BoostTokenAdded {
+        pub mint: Pubkey,
+        pub boost_vault: Pubkey,
+    }
+
+    #[event]
+    pub struct PriceOracleConfigured {
+        pub mint: Pubkey,
+        pub sources: Vec<Pubkey>,
+        pub max_age_secs: i64,
+        pub max_conf_bp: u16,
+    }
+
+    #[event]
+    pub struct FallbackPriceSet {
+        pub mint: Pubkey,
+        pub price_usd: u64,
+        pub set_by: Pubkey,
+    }
+
+    #[event]
+    pub struct BoostTokenStatusChanged {
+        pub mint: Pubkey,
+        pub is_active: bool,
+        pub changed_by: Pubkey,
     }
 
     #[event]
     pub struct Accrued {
         pub wexel_id: u64,
-        pub reward_usd: u64,
+        pub reward_usd: u64,           // Holder's share
+        pub platform_reward_usd: u64,  // Platform's share for time spent as collateral
         pub accrued_at: i64,
     }
 
@@ -83,46 +377,408 @@ pub mod solana_contracts {
         pub amount_usd: u64,
     }
 
+    #[event]
+    pub struct PlatformRewardsClaimed {
+        pub pool_id: u64,
+        pub to: Pubkey,
+        pub amount_usd: u64,
+    }
+
     #[event]
     pub struct Collateralized {
         pub wexel_id: u64,
         pub loan_usd: u64,
         pub ltv_bp: u16,
+        pub borrow_rate_bp: u16,
     }
 
     #[event]
     pub struct LoanRepaid {
         pub wexel_id: u64,
-        pub repaid_amount: u64,
+        pub repaid_amount: u64,           // Interest first, then principal
+        pub interest_paid: u64,
+        pub remaining_principal_usd: u64,
+        pub remaining_interest_usd: u64,
+        pub is_closed: bool,              // Debt fully repaid and position closed
     }
 
     #[event]
     pub struct Redeemed {
         pub wexel_id: u64,
-        pub principal_usd: u64,
+        pub principal_usd: u64,  // Paid to the owner, net of any settled loan
+        pub rewards_usd: u64,    // Paid to the owner, net of any settled loan
         pub redeemed_at: i64,
     }
 
+    #[event]
+    pub struct LoanSettledAtMaturity {
+        pub wexel_id: u64,
+        pub principal_usd: u64,          // Outstanding loan principal
+        pub interest_usd: u64,           // Outstanding interest
+        pub repaid_from_principal: u64,
+        pub repaid_from_rewards: u64,
+        pub shortfall_usd: u64,          // Debt left uncovered and written off
+        pub settled_at: i64,
+    }
+
     #[event]
     pub struct WexelFinalized {
         pub wexel_id: u64,
         pub finalized_at: i64,
     }
 
+    #[event]
+    pub struct TreasuryUpdated {
+        pub treasury: Pubkey,
+        pub updated_by: Pubkey,
+    }
+
+    #[event]
+    pub struct WexelListed {
+        pub wexel_id: u64,
+        pub seller: Pubkey,
+        pub payment_mint: Pubkey,
+        pub price: u64,
+        pub auction: Option<DutchAuction>,
+        pub listed_at: i64,
+    }
+
+    #[event]
+    pub struct WexelDelisted {
+        pub wexel_id: u64,
+        pub seller: Pubkey,
+    }
+
+    #[event]
+    pub struct WexelSold {
+        pub wexel_id: u64,
+        pub seller: Pubkey,
+        pub buyer: Pubkey,
+        pub payment_mint: Pubkey,
+        pub price: u64,
+        pub fee: u64,
+        pub sold_at: i64,
+    }
+
+    #[event]
+    pub struct OfferMade {
+        pub offer: Pubkey,
+        pub bidder: Pubkey,
+        pub wexel_id: Option<u64>,
+        pub criteria: OfferCriteria,
+        pub payment_mint: Pubkey,
+        pub amount: u64,
+        pub expires_at: i64,
+    }
+
+    #[event]
+    pub struct OfferCancelled {
+        pub offer: Pubkey,
+        pub bidder: Pubkey,
+        pub refunded: u64,
+        pub expired: bool,
+    }
+
+    #[event]
+    pub struct OfferAccepted {
+        pub offer: Pubkey,
+        pub wexel_id: u64,
+        pub seller: Pubkey,
+        pub bidder: Pubkey,
+        pub amount: u64,
+        pub fee: u64,
+        pub accepted_at: i64,
+    }
+
+    #[event]
+    pub struct WexelTransferred {
+        pub wexel_id: u64,
+        pub from: Pubkey,
+        pub to: Pubkey,
+        pub wexel: Pubkey,  // Record PDA under the new owner
+        pub transferred_at: i64,
+    }
+
     // Account structures
+    #[account]
+    pub struct GlobalConfig {
+        pub admin: Pubkey,
+        pub created_at: i64,
+        pub bump: u8,
+        pub wexel_count: u64,  // Last issued wexel id, ids start at 1
+        pub treasury: Pubkey,  // Receives platform fees
+        pub marketplace_fee_bp: u16,
+        pub collateral_user_share_bp: u16,  // Holder's share of yield while pledged; the platform gets the rest
+        pub paused: PauseFlags,
+        pub accepted_mints: Vec<Pubkey>,  // Deposit and payment mints, at most MAX_ACCEPTED_MINTS
+        pub pending_admin: Pubkey,             // Proposed admin; default when none
+        pub oracle_authority: Pubkey,          // Sets fallback prices
+        pub pending_oracle_authority: Pubkey,  // Proposed oracle authority; default when none
+        pub ltv_bp: u16,                  // Loan size as a share of principal
+        pub apy_tiers_bp: [u16; 4],       // Base APYs new pools may offer
+        pub boost_target_bp: u16,         // Boost value, as a share of principal, that earns the full boost
+        pub max_boost_apy_bp: u16,        // APY added by a full boost
+        pub param_change_delay: i64,      // Minimum seconds between scheduling and executing a change
+        pub param_change_count: u64,      // Last issued parameter change id
+    }
+
+    // A parameter update queued behind the timelock
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+    pub enum ParamChange {
+        LtvBp(u16),
+        ApyTiersBp([u16; 4]),
+        BoostTargetBp(u16),
+        MaxBoostApyBp(u16),
+        MarketplaceFeeBp(u16),
+        CollateralUserShareBp(u16),
+        Delay(i64),
+    }
+
+    impl ParamChange {
+        fn validate(&self) -> Result<()> {
+            let bps = math::BPS_DENOMINATOR as u16;
+            match *self {
+                ParamChange::LtvBp(ltv_bp) => {
+                    require!(ltv_bp > 0 && ltv_bp < bps, ErrorCode::InvalidParameter);
+                }
+                ParamChange::ApyTiersBp(tiers) => {
+                    require!(tiers.iter().all(|&apy_bp| apy_bp > 0 && apy_bp <= bps), ErrorCode::InvalidApy);
+                    require!(tiers.windows(2).all(|pair| pair[0] < pair[1]), ErrorCode::InvalidApy);
+                }
+                ParamChange::BoostTargetBp(target_bp) => {
+                    require!(target_bp > 0 && target_bp <= bps, ErrorCode::InvalidParameter);
+                }
+                ParamChange::MaxBoostApyBp(apy_bp) => {
+                    require!(apy_bp <= bps, ErrorCode::InvalidParameter);
+                }
+                ParamChange::MarketplaceFeeBp(fee_bp) => {
+                    require!(fee_bp <= MAX_MARKETPLACE_FEE_BP, ErrorCode::FeeTooHigh);
+                }
+                ParamChange::CollateralUserShareBp(user_share_bp) => {
+                    require!(user_share_bp <= bps, ErrorCode::InvalidYieldSplit);
+                }
+                ParamChange::Delay(delay) => {
+                    require!((0..=MAX_PARAM_CHANGE_DELAY).contains(&delay), ErrorCode::InvalidDelay);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl GlobalConfig {
+        fn propose_authority_change(&mut self, role: AuthorityRole, new_authority: Pubkey, proposed_by: Pubkey) -> Result<()> {
+            let current = match role {
+                AuthorityRole::Admin => self.admin,
+                AuthorityRole::Oracle => self.oracle_authority,
+            };
+            require!(
+                new_authority != Pubkey::default() && new_authority != current,
+                ErrorCode::InvalidAuthority
+            );
+
+            match role {
+                AuthorityRole::Admin => self.pending_admin = new_authority,
+                AuthorityRole::Oracle => self.pending_oracle_authority = new_authority,
+            }
+
+            emit!(AuthorityProposed {
+                role,
+                current,
+                proposed: new_authority,
+                proposed_by,
+            });
+
+            Ok(())
+        }
+
+        fn accept_authority_change(&mut self, role: AuthorityRole, new_authority: Pubkey) -> Result<()> {
+            let (current, pending) = match role {
+                AuthorityRole::Admin => (&mut self.admin, &mut self.pending_admin),
+                AuthorityRole::Oracle => (&mut self.oracle_authority, &mut self.pending_oracle_authority),
+            };
+            require!(*pending != Pubkey::default(), ErrorCode::NoPendingAuthority);
+            require!(*pending == new_authority, ErrorCode::Unauthorized);
+
+            let old_authority = *current;
+            *current = new_authority;
+            *pending = Pubkey::default();
+
+            emit!(AuthorityAccepted {
+                role,
+                old_authority,
+                new_authority,
+            });
+
+            Ok(())
+        }
+
+        fn apply(&mut self, change: ParamChange) {
+            match change {
+                ParamChange::LtvBp(ltv_bp) => self.ltv_bp = ltv_bp,
+                ParamChange::ApyTiersBp(tiers) => self.apy_tiers_bp = tiers,
+                ParamChange::BoostTargetBp(target_bp) => self.boost_target_bp = target_bp,
+                ParamChange::MaxBoostApyBp(apy_bp) => self.max_boost_apy_bp = apy_bp,
+                ParamChange::MarketplaceFeeBp(fee_bp) => self.marketplace_fee_bp = fee_bp,
+                ParamChange::CollateralUserShareBp(user_share_bp) => self.collateral_user_share_bp = user_share_bp,
+                ParamChange::Delay(delay) => self.param_change_delay = delay,
+            }
+        }
+    }
+
+    // Parameter change waiting for its ETA; closed when executed or cancelled
+    #[account]
+    pub struct QueuedParamChange {
+        pub change_id: u64,
+        pub change: ParamChange,
+        pub eta: i64,
+        pub scheduled_by: Pubkey,  // Paid the rent, refunded on close
+        pub scheduled_at: i64,
+        pub bump: u8,
+    }
+
+    // Roles handed over with propose_authority / accept_authority
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+    pub enum AuthorityRole {
+        Admin,
+        Oracle,
+    }
+
+    // Roles held through RoleAssignment PDAs, alongside the config admin
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+    pub enum Role {
+        Admin,      // grant_role, revoke_role
+        Pauser,     // set_paused, switching operations off only
+        Oracle,     // set_fallback_price
+        Treasurer,  // set_treasury, fund_rewards_vault
+        Keeper,     // accrue on any wexel
+    }
+
+    // One per (role, holder), seeded by [b"role", role, holder]
+    #[account]
+    pub struct RoleAssignment {
+        pub role: Role,
+        pub holder: Pubkey,
+        pub granted_by: Pubkey,  // Paid the rent, refunded on revoke
+        pub granted_at: i64,
+        pub bump: u8,
+    }
+
+    // M-of-N signer set; see the multisig module
+    #[account]
+    pub struct Multisig {
+        pub signers: Vec<Pubkey>,    // At most MAX_MULTISIG_SIGNERS
+        pub threshold: u8,
+        pub signer_set_seqno: u32,   // Bumped on every signer change, voiding open proposals
+        pub proposal_count: u64,     // Last issued proposal id
+        pub bump: u8,
+    }
+
+    // Privileged actions a multisig proposal can carry
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+    pub enum MultisigAction {
+        SetPaused(PauseFlags),
+        SetTreasury(Pubkey),
+        ProposeAuthority { role: AuthorityRole, new_authority: Pubkey },
+        AcceptAuthority(AuthorityRole),
+        SetFallbackPrice { mint: Pubkey, price_usd: u64 },
+        ChangeSigners { signers: Vec<Pubkey>, threshold: u8 },
+        // Any instruction of this program, signed by the multisig signer PDA
+        Invoke { accounts: Vec<InvokeAccount>, data: Vec<u8> },
+    }
+
+    // Account of an invoked instruction; only the multisig signer PDA signs
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+    pub struct InvokeAccount {
+        pub pubkey: Pubkey,
+        pub is_writable: bool,
+    }
+
+    // One action awaiting approvals; closed when executed or cancelled
+    #[account]
+    pub struct Proposal {
+        pub proposal_id: u64,
+        pub proposer: Pubkey,        // Paid the rent, refunded on close
+        pub action: MultisigAction,
+        pub approved: Vec<bool>,     // One flag per signer, in signer order
+        pub signer_set_seqno: u32,
+        pub created_at: i64,
+        pub bump: u8,
+    }
+
+    // Per-operation pause switches; exits (repay, delist, cancel offer) are never paused
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct PauseFlags {
+        pub deposit: bool,        // deposit
+        pub boost: bool,          // apply_boost
+        pub collateralize: bool,  // collateralize
+        pub claim: bool,          // accrue, mint_wexel_finalize, claim, claim_platform_rewards, redeem
+        pub marketplace: bool,    // transfer, list, buy, make and accept offers
+    }
+
+    impl PauseFlags {
+        // True when every operation paused in `other` is also paused here
+        fn covers(&self, other: &PauseFlags) -> bool {
+            (self.deposit || !other.deposit)
+                && (self.boost || !other.boost)
+                && (self.collateralize || !other.collateralize)
+                && (self.claim || !other.claim)
+                && (self.marketplace || !other.marketplace)
+        }
+    }
+
+    // Per-user wexel counter, used to derive the user's wexel PDAs
+    #[account]
+    pub struct UserPositions {
+        pub owner: Pubkey,
+        pub wexel_count: u64,
+        pub bump: u8,
+    }
+
+    // Linear price decay of a Dutch-auction listing
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+    pub struct DutchAuction {
+        pub start_price: u64,
+        pub floor_price: u64,
+        pub start_time: i64,
+        pub end_time: i64,
+    }
+
+    // What a criteria offer accepts; ignored when the offer names a wexel
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+    pub struct OfferCriteria {
+        pub pool_id: Option<u64>,
+        pub min_remaining_days: u32,
+        pub min_apy_bp: u16,  // Compared against base + boost APY
+        pub min_principal_usd: u64,
+    }
+
+    // One entry of a pool's term -> APY schedule
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+    pub struct LockTerm {
+        pub months: u8,
+        pub apy_bp: u16,
+    }
+
     #[account]
     pub struct Pool {
         pub id: u64,
         pub total_deposits: u64,
         pub total_loans: u64,
-        pub apy_bp: u16,
         pub created_at: i64,
+        pub usdt_mint: Pubkey,  // Deposit mint pinned at pool creation
+        pub bump: u8,
+        pub vault_bump: u8,
+        pub lock_terms: Vec<LockTerm>,  // Sorted by months, at most MAX_LOCK_TERMS
+        pub min_deposit_usd: u64,
+        pub max_deposit_usd: u64,
+        pub is_active: bool,
+        pub borrow_rate_bp: u16,  // Yearly simple interest charged on loans
     }
 
     #[account]
     pub struct Wexel {
         pub id: u64,
-        pub owner: Pubkey,
+        pub owner: Pubkey,          // Depositor or program-side recipient; seeds the PDA only. Stale after plain NFT transfers
         pub principal_usd: u64,
         pub apy_bp: u16,
         pub apy_boost_bp: u16,
@@ -135,22 +791,204 @@ pub mod solana_contracts {
         pub claimed_rewards: u64,
         pub last_accrued_at: i64,  // H-3 fix: track last accrual time
         pub is_locked: bool,        // H-1 fix: reentrancy guard
+        pub pool_id: u64,
+        pub owner_index: u64,       // Index in the owner's UserPositions counter
+        pub reward_remainder: u64,  // Sub-unit reward carried between accruals (see math::accrue_reward)
+        pub boost_value_usd: u64,   // Oracle value of all boost tokens applied so far
+        pub nft_mint: Pubkey,       // Token-2022 NFT mint (see the nft module); index wexels by this
+        pub collateralized_at: i64,           // Start of the current pledge
+        pub pending_collateral_seconds: u64,  // Pledged time of closed pledges not yet accrued
+    }
+
+    impl Wexel {
+        /// Accrue rewards from the last accrual up to `now`, capped at maturity.
+        /// The time spent as collateral earns the holder only
+        /// `collateral_user_share_bp`; the rest goes to the platform.
+        ///
+        /// Returns `(user_reward, platform_reward)`, or `None` if there is no
+        /// unaccrued time left.
+        pub fn accrue_rewards(&mut self, now: i64, collateral_user_share_bp: u16) -> Result<Option<(u64, u64)>> {
+            // Rewards stop at maturity
+            let accrual_end = now.min(self.matured_at);
+            if accrual_end <= self.last_accrued_at {
+                return Ok(None);
+            }
+
+            // Calculate rewards for the period since the last accrual only
+            let elapsed_seconds = (accrual_end - self.last_accrued_at) as u64;
+            let total_apy_bp = self.apy_bp as u32 + self.apy_boost_bp as u32;
+            let (reward, reward_remainder) = math::accrue_reward(
+                self.principal_usd,
+                total_apy_bp,
+                elapsed_seconds,
+                self.reward_remainder,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+
+            // Split off the platform share for the time spent as collateral:
+            // pledges closed since the last accrual plus the one still open
+            let mut collateral_seconds = self.pending_collateral_seconds;
+            if self.is_collateralized {
+                let pledged_from = self.collateralized_at.max(self.last_accrued_at);
+                collateral_seconds = collateral_seconds
+                    .checked_add(accrual_end.saturating_sub(pledged_from).max(0) as u64)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            let (user_reward, platform_reward) = math::split_collateral_reward(
+                reward,
+                elapsed_seconds,
+                collateral_seconds.min(elapsed_seconds),
+                collateral_user_share_bp,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+
+            self.total_rewards = self.total_rewards
+                .checked_add(user_reward)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.reward_remainder = reward_remainder;
+            self.last_accrued_at = accrual_end;  // H-3 fix: update last accrual time
+            self.pending_collateral_seconds = 0;
+
+            Ok(Some((user_reward, platform_reward)))
+        }
+    }
+
+    // Price proxy for a mint (see the oracle module)
+    #[account]
+    pub struct PriceOracle {
+        pub mint: Pubkey,
+        pub sources: Vec<Pubkey>,     // Pinned price accounts, at most oracle::MAX_PRICE_SOURCES
+        pub max_age_secs: i64,
+        pub max_conf_bp: u16,         // Max confidence interval relative to price
+        pub fallback_price_usd: u64,  // Admin price in micro-USD per whole token; 0 = unset
+        pub fallback_updated_at: i64,
+        pub bump: u8,
+    }
+
+    impl PriceOracle {
+        fn set_fallback_price(&mut self, price_usd: u64, set_by: Pubkey) -> Result<()> {
+            self.fallback_price_usd = price_usd;
+            self.fallback_updated_at = Clock::get()?.unix_timestamp;
+
+            emit!(FallbackPriceSet {
+                mint: self.mint,
+                price_usd,
+                set_by,
+            });
+
+            Ok(())
+        }
+    }
+
+    // Whitelisted boost coin with its vault; priced through its PriceOracle
+    #[account]
+    pub struct BoostToken {
+        pub mint: Pubkey,
+        pub decimals: u8,
+        pub is_active: bool,
+        pub total_received: u64,
+        pub bump: u8,
+        pub vault_bump: u8,
     }
 
     #[account]
     pub struct CollateralPosition {
         pub wexel_id: u64,
         pub owner: Pubkey,
-        pub loan_usd: u64,
+        pub loan_usd: u64,             // Amount disbursed
         pub ltv_bp: u16,
         pub created_at: i64,
         pub is_repaid: bool,
+        pub principal_usd: u64,        // Outstanding principal
+        pub interest_usd: u64,         // Accrued, unpaid interest
+        pub interest_remainder: u64,   // Sub-unit interest carried between accruals
+        pub borrow_rate_bp: u16,       // Pool borrow rate at origination
+        pub last_interest_at: i64,
+    }
+
+    impl CollateralPosition {
+        /// Accrue interest on the outstanding principal up to `now`
+        pub fn accrue_interest(&mut self, now: i64) -> Result<()> {
+            let elapsed_seconds = now.saturating_sub(self.last_interest_at).max(0) as u64;
+            // Simple interest uses the same formula as rewards, remainder included
+            let (interest, interest_remainder) = math::accrue_reward(
+                self.principal_usd,
+                self.borrow_rate_bp as u32,
+                elapsed_seconds,
+                self.interest_remainder,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+
+            self.interest_usd = self.interest_usd
+                .checked_add(interest)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.interest_remainder = interest_remainder;
+            self.last_interest_at = now.max(self.last_interest_at);
+            Ok(())
+        }
+
+        /// Outstanding principal plus accrued interest
+        pub fn debt_usd(&self) -> Option<u64> {
+            self.principal_usd.checked_add(self.interest_usd)
+        }
     }
 
+    // One shared rewards vault per pool
     #[account]
     pub struct RewardsVault {
-        pub total_rewards: u64,
-        pub distributed_rewards: u64,
+        pub pool_id: u64,
+        pub total_rewards: u64,        // Accrued across all wexels of the pool
+        pub distributed_rewards: u64,  // Paid out to wexel owners
+        pub total_funded: u64,         // Deposited by the admin
+        pub bump: u8,
+        pub token_vault_bump: u8,
+        pub platform_rewards: u64,     // Platform share accrued on collateralized wexels
+        pub platform_claimed: u64,     // Paid out to the treasury
+    }
+
+    impl RewardsVault {
+        /// Book rewards accrued on one of the pool's wexels
+        pub fn record_accrual(&mut self, user_reward: u64, platform_reward: u64) -> Result<()> {
+            let reward = user_reward
+                .checked_add(platform_reward)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.total_rewards = self.total_rewards
+                .checked_add(reward)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.platform_rewards = self.platform_rewards
+                .checked_add(platform_reward)
+                .ok_or(ErrorCode::MathOverflow)?;
+            Ok(())
+        }
+    }
+
+    // Sale of a wexel at a fixed price or by Dutch auction; the NFT sits in the listing's escrow account
+    #[account]
+    pub struct Listing {
+        pub wexel_id: u64,
+        pub seller: Pubkey,
+        pub wexel: Pubkey,
+        pub nft_mint: Pubkey,
+        pub payment_mint: Pubkey,
+        pub price: u64,                      // Fixed price, or the auction's start price
+        pub created_at: i64,
+        pub bump: u8,
+        pub auction: Option<DutchAuction>,   // None for fixed-price listings
+    }
+
+    // Standing bid for one wexel or any wexel matching criteria; the bid sits in the offer's escrow
+    #[account]
+    pub struct Offer {
+        pub bidder: Pubkey,
+        pub offer_id: u64,            // Bidder-chosen, seeds the PDA
+        pub wexel_id: Option<u64>,
+        pub criteria: OfferCriteria,
+        pub payment_mint: Pubkey,
+        pub amount: u64,
+        pub created_at: i64,
+        pub expires_at: i64,
+        pub bump: u8,
+        pub escrow_bump: u8,
     }
 
     // H-1 fix: Reentrancy guard for pool-level operations
@@ -160,408 +998,3341 @@ pub mod solana_contracts {
     }
 
     // Instructions
-    pub fn deposit(ctx: Context<Deposit>, pool_id: u64, principal_usd: u64) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        let wexel = &mut ctx.accounts.wexel;
-        let clock = Clock::get()?;
-
-        // Validate input
-        require!(principal_usd > 0, ErrorCode::InvalidAmount);
 
-        // Initialize pool if first deposit
-        if pool.id == 0 {
-            pool.id = pool_id;
-            pool.apy_bp = APY_BP;
-            pool.created_at = clock.unix_timestamp;
-        }
+    /// Create the global config; the signer becomes the admin
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        treasury: Pubkey,
+        accepted_mints: Vec<Pubkey>,
+        param_change_delay: i64,
+    ) -> Result<()> {
+        require!(accepted_mints.len() <= MAX_ACCEPTED_MINTS, ErrorCode::TooManyAcceptedMints);
+        ParamChange::Delay(param_change_delay).validate()?;
 
-        // Initialize wexel
-        wexel.id = pool_id;
-        wexel.owner = ctx.accounts.user.key();
-        wexel.principal_usd = principal_usd;
-        wexel.apy_bp = APY_BP;
-        wexel.apy_boost_bp = 0;
-        wexel.lock_period_months = 12; // Default 12 months
-        wexel.created_at = clock.unix_timestamp;
-        wexel.matured_at = clock.unix_timestamp + (12 * SECONDS_PER_MONTH) as i64;
-        wexel.is_collateralized = false;
-        wexel.is_finalized = false;
-        wexel.total_rewards = 0;
-        wexel.claimed_rewards = 0;
-        wexel.last_accrued_at = clock.unix_timestamp;  // H-3 fix: initialize
-        wexel.is_locked = false;  // H-1 fix: initialize reentrancy guard
+        let config = &mut ctx.accounts.config;
+        let clock = Clock::get()?;
 
-        // Update pool
-        pool.total_deposits = pool.total_deposits
-            .checked_add(principal_usd)
-            .ok_or(ErrorCode::MathOverflow)?;
+        config.admin = ctx.accounts.admin.key();
+        config.created_at = clock.unix_timestamp;
+        config.bump = ctx.bumps.config;
+        config.wexel_count = 0;
+        config.treasury = treasury;
+        config.marketplace_fee_bp = 0;
+        config.collateral_user_share_bp = DEFAULT_COLLATERAL_USER_SHARE_BP;
+        config.paused = PauseFlags::default();
+        config.accepted_mints = accepted_mints.clone();
+        config.pending_admin = Pubkey::default();
+        config.oracle_authority = ctx.accounts.admin.key();
+        config.pending_oracle_authority = Pubkey::default();
+        config.ltv_bp = DEFAULT_LTV_BP;
+        config.apy_tiers_bp = DEFAULT_APY_TIERS_BP;
+        config.boost_target_bp = math::BOOST_TARGET_BP;
+        config.max_boost_apy_bp = math::BOOST_APY_BP;
+        config.param_change_delay = param_change_delay;
+        config.param_change_count = 0;
 
-        // Emit event
-        emit!(WexelCreated {
-            id: wexel.id,
-            owner: wexel.owner,
-            principal_usd: wexel.principal_usd,
-            apy_bp: wexel.apy_bp,
-            lock_period_months: wexel.lock_period_months,
-            created_at: wexel.created_at,
+        emit!(ConfigInitialized {
+            admin: config.admin,
+            treasury,
+            accepted_mints,
+            initialized_at: clock.unix_timestamp,
         });
 
         Ok(())
     }
 
-    pub fn apply_boost(ctx: Context<ApplyBoost>, wexel_id: u64, amount: u64) -> Result<()> {
-        let wexel = &mut ctx.accounts.wexel;
+    /// Propose a new holder for `role` (admin only). Takes effect once the
+    /// proposed key calls `accept_authority`.
+    pub fn propose_authority(ctx: Context<UpdateConfig>, role: AuthorityRole, new_authority: Pubkey) -> Result<()> {
+        let proposed_by = ctx.accounts.admin.key();
+        ctx.accounts.config.propose_authority_change(role, new_authority, proposed_by)
+    }
 
-        // H-2 fix: Verify ownership before applying boost
-        require!(
-            wexel.owner == ctx.accounts.user.key(),
-            ErrorCode::Unauthorized
-        );
+    /// Take over `role`; the signer must be the proposed key
+    pub fn accept_authority(ctx: Context<AcceptAuthority>, role: AuthorityRole) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        ctx.accounts.config.accept_authority_change(role, new_authority)
+    }
 
-        // Validate input
-        require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+    /// Withdraw a pending proposal for `role` (admin only)
+    pub fn cancel_authority_proposal(ctx: Context<UpdateConfig>, role: AuthorityRole) -> Result<()> {
+        let config = &mut ctx.accounts.config;
 
-        // Calculate boost APY
-        let boost_target = (wexel.principal_usd * BOOST_TARGET_BP as u64) / 10000;
-        let boost_ratio = if boost_target > 0 {
-            amount.min(boost_target) as f64 / boost_target as f64
-        } else {
-            0.0
+        let pending = match role {
+            AuthorityRole::Admin => &mut config.pending_admin,
+            AuthorityRole::Oracle => &mut config.pending_oracle_authority,
         };
-        let boost_apy_bp = (boost_ratio * BOOST_APY_BP as f64) as u16;
+        require!(*pending != Pubkey::default(), ErrorCode::NoPendingAuthority);
 
-        // Update wexel
-        wexel.apy_boost_bp = boost_apy_bp;
+        let cancelled = *pending;
+        *pending = Pubkey::default();
 
-        // Emit event
-        emit!(BoostApplied {
-            wexel_id: wexel.id,
-            apy_boost_bp: boost_apy_bp,
-            value_usd: amount,
+        emit!(AuthorityProposalCancelled {
+            role,
+            cancelled,
+            cancelled_by: ctx.accounts.admin.key(),
         });
 
         Ok(())
     }
 
-    pub fn mint_wexel_finalize(ctx: Context<MintWexelFinalize>, wexel_id: u64) -> Result<()> {
-        let wexel = &mut ctx.accounts.wexel;
-        let clock = Clock::get()?;
+    /// Give `holder` a role (admin or admin role)
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == ctx.accounts.config.admin || ctx.accounts.admin_role.is_some(),
+            ErrorCode::Unauthorized
+        );
 
-        // Validate wexel
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
-        require!(clock.unix_timestamp >= wexel.matured_at, ErrorCode::WexelNotMatured);
+        let role_assignment = &mut ctx.accounts.role_assignment;
+        role_assignment.role = role;
+        role_assignment.holder = holder;
+        role_assignment.granted_by = authority;
+        role_assignment.granted_at = Clock::get()?.unix_timestamp;
+        role_assignment.bump = ctx.bumps.role_assignment;
 
-        // Finalize wexel
-        wexel.is_finalized = true;
+        emit!(RoleGranted {
+            role,
+            holder,
+            granted_by: authority,
+        });
 
-        // Emit event
-        emit!(WexelFinalized {
-            wexel_id: wexel.id,
-            finalized_at: clock.unix_timestamp,
+        Ok(())
+    }
+
+    /// Take a role away from `holder` (admin or admin role)
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == ctx.accounts.config.admin || ctx.accounts.admin_role.is_some(),
+            ErrorCode::Unauthorized
+        );
+
+        emit!(RoleRevoked {
+            role,
+            holder,
+            revoked_by: authority,
         });
 
+        // Role assignment is closed to its granter on exit
         Ok(())
     }
 
-    pub fn accrue(ctx: Context<Accrue>, wexel_id: u64) -> Result<()> {
-        let wexel = &mut ctx.accounts.wexel;
-        let rewards_vault = &mut ctx.accounts.rewards_vault;
-        let clock = Clock::get()?;
+    /// Create the multisig with its signer set and threshold (admin only)
+    pub fn create_multisig(ctx: Context<CreateMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        multisig::validate_signers(&signers, threshold)?;
 
-        // Validate wexel
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.signers = signers.clone();
+        multisig.threshold = threshold;
+        multisig.signer_set_seqno = 0;
+        multisig.proposal_count = 0;
+        multisig.bump = ctx.bumps.multisig;
 
-        // H-3 fix: Ensure at least 1 day has passed since last accrual
-        let time_since_last_accrual = clock.unix_timestamp - wexel.last_accrued_at;
+        emit!(MultisigCreated {
+            signers,
+            threshold,
+            created_by: ctx.accounts.admin.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Open a proposal for `action`; counts as the proposer's approval (multisig signers only)
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: MultisigAction) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let proposer = ctx.accounts.proposer.key();
+        let index = multisig::signer_index(&multisig.signers, &proposer)?;
+
+        match &action {
+            MultisigAction::ChangeSigners { signers, threshold } => {
+                multisig::validate_signers(signers, *threshold)?;
+            }
+            MultisigAction::Invoke { accounts, data } => {
+                multisig::validate_invoke(accounts, data, &multisig.key())?;
+            }
+            _ => {}
+        }
+
+        multisig.proposal_count = multisig.proposal_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let mut approved = vec![false; multisig.signers.len()];
+        approved[index] = true;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_id = multisig.proposal_count;
+        proposal.proposer = proposer;
+        proposal.action = action.clone();
+        proposal.approved = approved;
+        proposal.signer_set_seqno = multisig.signer_set_seqno;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.bump = ctx.bumps.proposal;
+
+        emit!(ProposalCreated {
+            proposal_id: proposal.proposal_id,
+            proposer,
+            action,
+        });
+
+        Ok(())
+    }
+
+    /// Approve a proposal (multisig signers only)
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, proposal_id: u64) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        let signer = ctx.accounts.signer.key();
+
+        require!(proposal.signer_set_seqno == multisig.signer_set_seqno, ErrorCode::StaleProposal);
+        let index = multisig::signer_index(&multisig.signers, &signer)?;
+        require!(!proposal.approved[index], ErrorCode::AlreadyApproved);
+        proposal.approved[index] = true;
+
+        emit!(ProposalApproved {
+            proposal_id,
+            signer,
+            approvals: multisig::approval_count(&proposal.approved) as u8,
+        });
+
+        Ok(())
+    }
+
+    /// Apply an approved proposal's action (permissionless once the threshold is reached)
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+        proposal_id: u64,
+    ) -> Result<()> {
+        let signer_key = ctx.accounts.multisig_signer.key();
+        let multisig = &mut ctx.accounts.multisig;
+        let proposal = &ctx.accounts.proposal;
+        let config = &mut ctx.accounts.config;
+
+        require!(proposal.signer_set_seqno == multisig.signer_set_seqno, ErrorCode::StaleProposal);
         require!(
-            time_since_last_accrual >= SECONDS_PER_DAY as i64,
-            ErrorCode::TooEarlyToAccrue
+            multisig::approval_count(&proposal.approved) >= multisig.threshold as usize,
+            ErrorCode::ThresholdNotReached
         );
 
-        // Calculate rewards
-        let days_elapsed = (clock.unix_timestamp - wexel.created_at) / SECONDS_PER_DAY as i64;
-        let total_apy_bp = wexel.apy_bp + wexel.apy_boost_bp;
-        let daily_reward = (wexel.principal_usd * total_apy_bp as u64) / (365 * 10000);
-        let total_rewards = daily_reward * days_elapsed as u64;
+        // The multisig acts with whatever roles have been handed to its signer PDA
+        match proposal.action.clone() {
+            MultisigAction::SetPaused(paused) => {
+                require!(config.admin == signer_key, ErrorCode::Unauthorized);
+                config.paused = paused;
 
-        // Update wexel
-        wexel.total_rewards = total_rewards;
-        wexel.last_accrued_at = clock.unix_timestamp;  // H-3 fix: update last accrual time
+                emit!(PauseFlagsChanged {
+                    paused,
+                    changed_by: signer_key,
+                });
+            }
+            MultisigAction::SetTreasury(treasury) => {
+                require!(config.admin == signer_key, ErrorCode::Unauthorized);
+                config.treasury = treasury;
 
-        // Update rewards vault
-        rewards_vault.total_rewards = rewards_vault.total_rewards
-            .checked_add(total_rewards)
-            .ok_or(ErrorCode::MathOverflow)?;
+                emit!(TreasuryUpdated {
+                    treasury,
+                    updated_by: signer_key,
+                });
+            }
+            MultisigAction::ProposeAuthority { role, new_authority } => {
+                require!(config.admin == signer_key, ErrorCode::Unauthorized);
+                config.propose_authority_change(role, new_authority, signer_key)?;
+            }
+            MultisigAction::AcceptAuthority(role) => {
+                config.accept_authority_change(role, signer_key)?;
+            }
+            MultisigAction::SetFallbackPrice { mint, price_usd } => {
+                require!(config.oracle_authority == signer_key, ErrorCode::Unauthorized);
+                let price_oracle = ctx.accounts.price_oracle
+                    .as_mut()
+                    .ok_or(ErrorCode::InvalidOracleConfig)?;
+                require!(price_oracle.mint == mint, ErrorCode::InvalidOracleConfig);
+                price_oracle.set_fallback_price(price_usd, signer_key)?;
+            }
+            MultisigAction::ChangeSigners { signers, threshold } => {
+                multisig::validate_signers(&signers, threshold)?;
+                multisig.signers = signers.clone();
+                multisig.threshold = threshold;
+                multisig.signer_set_seqno = multisig.signer_set_seqno
+                    .checked_add(1)
+                    .ok_or(ErrorCode::MathOverflow)?;
 
-        // Emit event
-        emit!(Accrued {
-            wexel_id: wexel.id,
-            reward_usd: total_rewards,
-            accrued_at: clock.unix_timestamp,
+                emit!(MultisigSignersChanged {
+                    signers,
+                    threshold,
+                    signer_set_seqno: multisig.signer_set_seqno,
+                });
+            }
+            MultisigAction::Invoke { accounts, data } => {
+                let ix = Instruction {
+                    program_id: crate::ID,
+                    accounts: accounts
+                        .iter()
+                        .map(|account| AccountMeta {
+                            pubkey: account.pubkey,
+                            is_signer: account.pubkey == signer_key,
+                            is_writable: account.is_writable,
+                        })
+                        .collect(),
+                    data,
+                };
+                let mut account_infos = ctx.remaining_accounts.to_vec();
+                account_infos.push(ctx.accounts.multisig_signer.to_account_info());
+                invoke_signed(
+                    &ix,
+                    &account_infos,
+                    &[&[b"multisig_signer", &[ctx.bumps.multisig_signer]]],
+                )?;
+
+                // The invoked instruction may have written accounts held here; reload so exit keeps its writes
+                config.reload()?;
+                if let Some(price_oracle) = ctx.accounts.price_oracle.as_mut() {
+                    price_oracle.reload()?;
+                }
+            }
+        }
+
+        emit!(ProposalExecuted {
+            proposal_id,
+            executed_by: ctx.accounts.executor.key(),
         });
 
+        // Proposal is closed to its proposer on exit
         Ok(())
     }
 
-    pub fn claim(ctx: Context<Claim>, wexel_id: u64) -> Result<()> {
-        let wexel = &mut ctx.accounts.wexel;
-        let rewards_vault = &mut ctx.accounts.rewards_vault;
+    /// Withdraw a proposal (proposer only)
+    pub fn cancel_proposal(ctx: Context<CancelProposal>, proposal_id: u64) -> Result<()> {
+        emit!(ProposalCancelled {
+            proposal_id,
+            cancelled_by: ctx.accounts.proposer.key(),
+        });
 
-        // H-1 fix: Reentrancy guard
-        require!(!wexel.is_locked, ErrorCode::ReentrancyDetected);
-        wexel.is_locked = true;
+        // Proposal is closed to its proposer on exit
+        Ok(())
+    }
 
-        // Validate wexel
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(wexel.total_rewards > 0, ErrorCode::InvalidAmount);
+    /// Queue a parameter change that anyone may execute once `eta` has passed (admin only)
+    pub fn schedule_param_change(ctx: Context<ScheduleParamChange>, change: ParamChange, eta: i64) -> Result<()> {
+        change.validate()?;
 
-        // Calculate claimable amount
-        let claimable_amount = wexel.total_rewards - wexel.claimed_rewards;
-        require!(claimable_amount > 0, ErrorCode::InvalidAmount);
+        let config = &mut ctx.accounts.config;
+        let clock = Clock::get()?;
 
-        // Update wexel
-        wexel.claimed_rewards = wexel.claimed_rewards
-            .checked_add(claimable_amount)
+        let earliest = clock.unix_timestamp
+            .checked_add(config.param_change_delay)
             .ok_or(ErrorCode::MathOverflow)?;
+        require!(eta >= earliest, ErrorCode::EtaTooEarly);
 
-        // Update rewards vault
-        rewards_vault.distributed_rewards = rewards_vault.distributed_rewards
-            .checked_add(claimable_amount)
+        config.param_change_count = config.param_change_count
+            .checked_add(1)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        // Emit event
-        emit!(Claimed {
-            wexel_id: wexel.id,
-            to: ctx.accounts.user.key(),
-            amount_usd: claimable_amount,
+        let queued = &mut ctx.accounts.queued_change;
+        queued.change_id = config.param_change_count;
+        queued.change = change;
+        queued.eta = eta;
+        queued.scheduled_by = ctx.accounts.admin.key();
+        queued.scheduled_at = clock.unix_timestamp;
+        queued.bump = ctx.bumps.queued_change;
+
+        emit!(ParamChangeScheduled {
+            change_id: queued.change_id,
+            change,
+            eta,
+            scheduled_by: queued.scheduled_by,
         });
 
-        // H-1 fix: Release reentrancy lock
-        wexel.is_locked = false;
+        Ok(())
+    }
+
+    /// Apply a queued parameter change whose ETA has passed (permissionless)
+    pub fn execute_param_change(ctx: Context<ExecuteParamChange>, change_id: u64) -> Result<()> {
+        let queued = &ctx.accounts.queued_change;
+        let clock = Clock::get()?;
+
+        require!(clock.unix_timestamp >= queued.eta, ErrorCode::ParamChangeNotReady);
+
+        ctx.accounts.config.apply(queued.change);
+
+        emit!(ParamChangeExecuted {
+            change_id,
+            change: queued.change,
+            executed_by: ctx.accounts.executor.key(),
+            executed_at: clock.unix_timestamp,
+        });
 
+        // Queued change is closed to its scheduler on exit
         Ok(())
     }
 
-    pub fn collateralize(ctx: Context<Collateralize>, wexel_id: u64) -> Result<()> {
-        let wexel = &mut ctx.accounts.wexel;
-        let collateral_position = &mut ctx.accounts.collateral_position;
+    /// Drop a queued parameter change before its ETA (admin only)
+    pub fn cancel_param_change(ctx: Context<CancelParamChange>, change_id: u64) -> Result<()> {
+        let queued = &ctx.accounts.queued_change;
         let clock = Clock::get()?;
 
-        // H-1 fix: Reentrancy guard
-        require!(!wexel.is_locked, ErrorCode::ReentrancyDetected);
-        wexel.is_locked = true;
+        require!(clock.unix_timestamp < queued.eta, ErrorCode::ParamChangeDue);
 
-        // Validate wexel
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
-        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        emit!(ParamChangeCancelled {
+            change_id,
+            change: queued.change,
+            cancelled_by: ctx.accounts.admin.key(),
+        });
+
+        // Queued change is closed to its scheduler on exit
+        Ok(())
+    }
+
+    /// Pause or resume individual operations (admin or pauser). Pausers may only
+    /// switch operations off; resuming takes the admin.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: PauseFlags) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let authority = ctx.accounts.authority.key();
+
+        if authority != config.admin {
+            require!(
+                ctx.accounts.pauser_role.is_some() && paused.covers(&config.paused),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        config.paused = paused;
+
+        emit!(PauseFlagsChanged {
+            paused,
+            changed_by: authority,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the list of accepted deposit and payment mints (admin only)
+    pub fn set_accepted_mints(ctx: Context<UpdateConfig>, accepted_mints: Vec<Pubkey>) -> Result<()> {
+        require!(accepted_mints.len() <= MAX_ACCEPTED_MINTS, ErrorCode::TooManyAcceptedMints);
+
+        ctx.accounts.config.accepted_mints = accepted_mints.clone();
+
+        emit!(AcceptedMintsUpdated {
+            accepted_mints,
+            updated_by: ctx.accounts.admin.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Create a pool with its vault (admin only)
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+        pool_id: u64,
+        lock_terms: Vec<LockTerm>,
+        min_deposit_usd: u64,
+        max_deposit_usd: u64,
+        borrow_rate_bp: u16,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+
+        // Validate parameters
         require!(
-            wexel.owner == ctx.accounts.user.key(),
-            ErrorCode::Unauthorized
+            !lock_terms.is_empty() && lock_terms.len() <= MAX_LOCK_TERMS,
+            ErrorCode::InvalidLockPeriod
+        );
+        for term in lock_terms.iter() {
+            require!(ctx.accounts.config.apy_tiers_bp.contains(&term.apy_bp), ErrorCode::InvalidApy);
+            require!(
+                (MIN_LOCK_PERIOD_MONTHS..=MAX_LOCK_PERIOD_MONTHS).contains(&term.months),
+                ErrorCode::InvalidLockPeriod
+            );
+        }
+        // Longer locks must never earn less than shorter ones
+        for pair in lock_terms.windows(2) {
+            require!(pair[0].months < pair[1].months, ErrorCode::InvalidLockPeriod);
+            require!(pair[0].apy_bp <= pair[1].apy_bp, ErrorCode::InvalidApy);
+        }
+        require!(
+            min_deposit_usd > 0 && min_deposit_usd <= max_deposit_usd,
+            ErrorCode::InvalidDepositLimits
+        );
+        require!(borrow_rate_bp <= MAX_BORROW_RATE_BP, ErrorCode::InvalidBorrowRate);
+        require!(
+            ctx.accounts.config.accepted_mints.contains(&ctx.accounts.usdt_mint.key()),
+            ErrorCode::MintNotAccepted
+        );
+        require!(
+            ctx.accounts.usdt_mint.decimals == math::USD_DECIMALS,
+            ErrorCode::InvalidMint
         );
 
-        // Calculate loan amount
-        let loan_usd = (wexel.principal_usd * LTV_BP as u64) / 10000;
-        require!(loan_usd > 0, ErrorCode::InvalidLoanAmount);
+        // Initialize pool
+        pool.id = pool_id;
+        pool.total_deposits = 0;
+        pool.total_loans = 0;
+        pool.created_at = clock.unix_timestamp;
+        pool.usdt_mint = ctx.accounts.usdt_mint.key();
+        pool.bump = ctx.bumps.pool;
+        pool.vault_bump = ctx.bumps.pool_vault;
+        pool.lock_terms = lock_terms.clone();
+        pool.min_deposit_usd = min_deposit_usd;
+        pool.max_deposit_usd = max_deposit_usd;
+        pool.is_active = true;
+        pool.borrow_rate_bp = borrow_rate_bp;
 
-        // Initialize collateral position
-        collateral_position.wexel_id = wexel_id;
-        collateral_position.owner = ctx.accounts.user.key();
-        collateral_position.loan_usd = loan_usd;
-        collateral_position.ltv_bp = LTV_BP;
-        collateral_position.created_at = clock.unix_timestamp;
-        collateral_position.is_repaid = false;
+        emit!(PoolCreated {
+            pool_id,
+            usdt_mint: pool.usdt_mint,
+            lock_terms,
+            min_deposit_usd,
+            max_deposit_usd,
+            borrow_rate_bp,
+            created_at: clock.unix_timestamp,
+        });
 
-        // Update wexel
-        wexel.is_collateralized = true;
+        Ok(())
+    }
 
-        // Emit event
-        emit!(Collateralized {
-            wexel_id: wexel.id,
-            loan_usd: loan_usd,
-            ltv_bp: LTV_BP,
-        });
+    /// Open or close a pool for new deposits (admin only)
+    pub fn set_pool_active(ctx: Context<SetPoolActive>, pool_id: u64, is_active: bool) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
 
-        // H-1 fix: Release reentrancy lock
-        wexel.is_locked = false;
+        require!(pool.id == pool_id, ErrorCode::InvalidPool);
+
+        pool.is_active = is_active;
+
+        emit!(PoolStatusChanged {
+            pool_id,
+            is_active,
+            changed_by: ctx.accounts.admin.key(),
+        });
 
         Ok(())
     }
 
-    pub fn repay_loan(ctx: Context<RepayLoan>, wexel_id: u64, repay_amount: u64) -> Result<()> {
-        let wexel = &mut ctx.accounts.wexel;
-        let collateral_position = &mut ctx.accounts.collateral_position;
+    /// Create the shared rewards vault of a pool (admin only)
+    pub fn initialize_rewards_vault(ctx: Context<InitializeRewardsVault>, pool_id: u64) -> Result<()> {
+        let rewards_vault = &mut ctx.accounts.rewards_vault;
 
-        // H-1 fix: Reentrancy guard
-        require!(!wexel.is_locked, ErrorCode::ReentrancyDetected);
-        wexel.is_locked = true;
+        rewards_vault.pool_id = pool_id;
+        rewards_vault.total_rewards = 0;
+        rewards_vault.distributed_rewards = 0;
+        rewards_vault.total_funded = 0;
+        rewards_vault.bump = ctx.bumps.rewards_vault;
+        rewards_vault.token_vault_bump = ctx.bumps.rewards_token_vault;
+        rewards_vault.platform_rewards = 0;
+        rewards_vault.platform_claimed = 0;
 
-        // Validate wexel
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(wexel.is_collateralized, ErrorCode::WexelNotCollateralized);
-        require!(!collateral_position.is_repaid, ErrorCode::InvalidRepaymentAmount);
+        emit!(RewardsVaultInitialized {
+            pool_id,
+            rewards_vault: rewards_vault.key(),
+            token_vault: ctx.accounts.rewards_token_vault.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Top up a pool's rewards vault from the signer's token account (admin or treasurer)
+    pub fn fund_rewards_vault(ctx: Context<FundRewardsVault>, pool_id: u64, amount: u64) -> Result<()> {
         require!(
-            wexel.owner == ctx.accounts.user.key(),
+            ctx.accounts.authority.key() == ctx.accounts.config.admin || ctx.accounts.treasurer_role.is_some(),
             ErrorCode::Unauthorized
         );
+        require!(amount > 0, ErrorCode::InvalidAmount);
 
-        // Validate repayment amount
-        require!(repay_amount > 0, ErrorCode::InvalidAmount);
-        require!(repay_amount >= collateral_position.loan_usd, ErrorCode::InvalidRepaymentAmount);
-
-        // Update collateral position
-        collateral_position.is_repaid = true;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority_token_account.to_account_info(),
+            to: ctx.accounts.rewards_token_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
 
-        // Update wexel
-        wexel.is_collateralized = false;
+        let rewards_vault = &mut ctx.accounts.rewards_vault;
+        rewards_vault.total_funded = rewards_vault.total_funded
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
 
-        // Emit event
-        emit!(LoanRepaid {
-            wexel_id: wexel.id,
-            repaid_amount: repay_amount,
+        emit!(RewardsVaultFunded {
+            pool_id,
+            amount,
+            total_funded: rewards_vault.total_funded,
+            funded_by: ctx.accounts.authority.key(),
         });
 
-        // H-1 fix: Release reentrancy lock
-        wexel.is_locked = false;
-
         Ok(())
     }
 
-    pub fn redeem(ctx: Context<Redeem>, wexel_id: u64) -> Result<()> {
+    pub fn deposit(
+        ctx: Context<Deposit>,
+        pool_id: u64,
+        principal_usd: u64,
+        lock_period_months: u8,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused.deposit, ErrorCode::OperationPaused);
+
+        let config = &mut ctx.accounts.config;
+        let user_positions = &mut ctx.accounts.user_positions;
+        let pool = &mut ctx.accounts.pool;
         let wexel = &mut ctx.accounts.wexel;
         let clock = Clock::get()?;
 
-        // Validate wexel
-        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
-        require!(wexel.is_finalized, ErrorCode::WexelNotFinalized);
-        require!(clock.unix_timestamp >= wexel.matured_at, ErrorCode::WexelNotMatured);
+        // Validate input
+        require!(principal_usd > 0, ErrorCode::InvalidAmount);
+        require!(pool.id == pool_id, ErrorCode::InvalidPool);
+        require!(pool.is_active, ErrorCode::PoolInactive);
+        require!(
+            config.accepted_mints.contains(&pool.usdt_mint),
+            ErrorCode::MintNotAccepted
+        );
+        require!(principal_usd >= pool.min_deposit_usd, ErrorCode::DepositTooSmall);
+        require!(principal_usd <= pool.max_deposit_usd, ErrorCode::DepositTooLarge);
+
+        // Look up the APY for the chosen term
+        let term = pool.lock_terms
+            .iter()
+            .find(|term| term.months == lock_period_months)
+            .copied()
+            .ok_or(ErrorCode::InvalidLockPeriod)?;
+
+        // Move principal into pool custody
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.pool_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, principal_usd)?;
+
+        // Assign a globally unique id and bump the owner's position counter
+        let wexel_id = config.wexel_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        config.wexel_count = wexel_id;
+
+        if user_positions.owner == Pubkey::default() {
+            user_positions.owner = ctx.accounts.user.key();
+            user_positions.bump = ctx.bumps.user_positions;
+        }
+        let owner_index = user_positions.wexel_count;
+        user_positions.wexel_count = owner_index
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Initialize wexel
+        wexel.id = wexel_id;
+        wexel.owner = ctx.accounts.user.key();
+        wexel.principal_usd = principal_usd;
+        wexel.apy_bp = term.apy_bp;
+        wexel.apy_boost_bp = 0;
+        wexel.lock_period_months = term.months;
+        wexel.created_at = clock.unix_timestamp;
+        wexel.matured_at = clock.unix_timestamp + (term.months as u64 * SECONDS_PER_MONTH) as i64;
+        wexel.is_collateralized = false;
+        wexel.is_finalized = false;
+        wexel.total_rewards = 0;
+        wexel.claimed_rewards = 0;
+        wexel.last_accrued_at = clock.unix_timestamp;  // H-3 fix: initialize
+        wexel.is_locked = false;  // H-1 fix: initialize reentrancy guard
+        wexel.pool_id = pool_id;
+        wexel.owner_index = owner_index;
+        wexel.reward_remainder = 0;
+        wexel.boost_value_usd = 0;
+        wexel.nft_mint = ctx.accounts.wexel_mint.key();
+        wexel.collateralized_at = 0;
+        wexel.pending_collateral_seconds = 0;
+
+        // Update pool
+        pool.total_deposits = pool.total_deposits
+            .checked_add(principal_usd)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Emit event
+        emit!(WexelCreated {
+            id: wexel.id,
+            pool_id,
+            owner: wexel.owner,
+            nft_mint: wexel.nft_mint,
+            principal_usd: wexel.principal_usd,
+            apy_bp: wexel.apy_bp,
+            lock_period_months: wexel.lock_period_months,
+            created_at: wexel.created_at,
+        });
+
+        // Mint the wexel NFT to the depositor, described by on-mint metadata
+        let config_seeds = &[b"config".as_ref(), &[ctx.accounts.config.bump]];
+        let config_signer = &[&config_seeds[..]];
+
+        nft::sync_wexel_metadata(
+            nft::MetadataAccounts {
+                mint: &ctx.accounts.wexel_mint.to_account_info(),
+                authority: &ctx.accounts.config.to_account_info(),
+                payer: &ctx.accounts.user.to_account_info(),
+                token_program: &ctx.accounts.token_2022_program.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            &ctx.accounts.wexel,
+            config_signer,
+        )?;
+
+        let cpi_accounts = token_interface::MintTo {
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.wexel_nft_account.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            config_signer,
+        );
+        token_interface::mint_to(cpi_ctx, 1)?;
+
+        // Supply is fixed at one token
+        let cpi_accounts = token_interface::SetAuthority {
+            current_authority: ctx.accounts.config.to_account_info(),
+            account_or_mint: ctx.accounts.wexel_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            config_signer,
+        );
+        token_interface::set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+
+        Ok(())
+    }
+
+    /// Create the price proxy for a mint (admin only)
+    pub fn initialize_price_oracle(
+        ctx: Context<InitializePriceOracle>,
+        sources: Vec<Pubkey>,
+        max_age_secs: i64,
+        max_conf_bp: u16,
+    ) -> Result<()> {
+        oracle::validate_config(&sources, max_age_secs, max_conf_bp)?;
+
+        let price_oracle = &mut ctx.accounts.price_oracle;
+        price_oracle.mint = ctx.accounts.mint.key();
+        price_oracle.sources = sources;
+        price_oracle.max_age_secs = max_age_secs;
+        price_oracle.max_conf_bp = max_conf_bp;
+        price_oracle.fallback_price_usd = 0;
+        price_oracle.fallback_updated_at = 0;
+        price_oracle.bump = ctx.bumps.price_oracle;
+
+        emit!(PriceOracleConfigured {
+            mint: price_oracle.mint,
+            sources: price_oracle.sources.clone(),
+            max_age_secs,
+            max_conf_bp,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the price sources and acceptance limits of a price proxy (admin only)
+    pub fn update_price_oracle(
+        ctx: Context<UpdatePriceOracle>,
+        sources: Vec<Pubkey>,
+        max_age_secs: i64,
+        max_conf_bp: u16,
+    ) -> Result<()> {
+        oracle::validate_config(&sources, max_age_secs, max_conf_bp)?;
+
+        let price_oracle = &mut ctx.accounts.price_oracle;
+        price_oracle.sources = sources;
+        price_oracle.max_age_secs = max_age_secs;
+        price_oracle.max_conf_bp = max_conf_bp;
+
+        emit!(PriceOracleConfigured {
+            mint: price_oracle.mint,
+            sources: price_oracle.sources.clone(),
+            max_age_secs,
+            max_conf_bp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the price used when no source is usable; 0 disables the fallback (oracle authority or oracle role)
+    pub fn set_fallback_price(ctx: Context<SetFallbackPrice>, price_usd: u64) -> Result<()> {
+        require!(
+            ctx.accounts.oracle.key() == ctx.accounts.config.oracle_authority || ctx.accounts.oracle_role.is_some(),
+            ErrorCode::Unauthorized
+        );
+
+        let set_by = ctx.accounts.oracle.key();
+        ctx.accounts.price_oracle.set_fallback_price(price_usd, set_by)
+    }
+
+    /// Handles instructions without an IDL entry. `mock-oracle` builds route
+    /// the mock price write here so it never appears in the production IDL;
+    /// other builds reject every unknown instruction as Anchor does by default.
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        #[cfg(feature = "mock-oracle")]
+        if let Some(args) = data.strip_prefix(&oracle::WRITE_MOCK_PRICE_DISCRIMINATOR) {
+            return oracle::write_mock_price(program_id, accounts, args);
+        }
+
+        let _ = (program_id, accounts, data);
+        Err(anchor_lang::error::ErrorCode::InstructionFallbackNotFound.into())
+    }
+
+    /// Whitelist a boost coin and create its vault (admin only).
+    /// The coin's price oracle must already exist.
+    pub fn add_boost_token(ctx: Context<AddBoostToken>) -> Result<()> {
+        let boost_token = &mut ctx.accounts.boost_token;
+        boost_token.mint = ctx.accounts.boost_mint.key();
+        boost_token.decimals = ctx.accounts.boost_mint.decimals;
+        boost_token.is_active = true;
+        boost_token.total_received = 0;
+        boost_token.bump = ctx.bumps.boost_token;
+        boost_token.vault_bump = ctx.bumps.boost_vault;
+
+        emit!(BoostTokenAdded {
+            mint: boost_token.mint,
+            boost_vault: ctx.accounts.boost_vault.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Enable or disable a whitelisted boost coin (admin only)
+    pub fn set_boost_token_active(ctx: Context<SetBoostTokenActive>, is_active: bool) -> Result<()> {
+        let boost_token = &mut ctx.accounts.boost_token;
+        boost_token.is_active = is_active;
+
+        emit!(BoostTokenStatusChanged {
+            mint: boost_token.mint,
+            is_active,
+            changed_by: ctx.accounts.admin.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Pay `amount` base units of a whitelisted boost coin into the boost vault.
+    /// The boost is priced on-chain and accumulates across calls.
+    pub fn apply_boost(ctx: Context<ApplyBoost>, wexel_id: u64, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.boost, ErrorCode::OperationPaused);
+
+        let wexel = &mut ctx.accounts.wexel;
+        let boost_token = &mut ctx.accounts.boost_token;
+        let clock = Clock::get()?;
+
+        // H-2 fix: Verify the signer holds the wexel NFT before applying boost
+        require!(
+            ctx.accounts.wexel_nft_account.owner == ctx.accounts.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Validate input
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(boost_token.is_active, ErrorCode::BoostTokenInactive);
+
+        // Price the boost tokens through the price proxy; sources come in remaining accounts
+        let price_usd = oracle::resolve_price_usd(
+            &ctx.accounts.price_oracle,
+            ctx.remaining_accounts,
+            clock.unix_timestamp,
+        )?;
+        let value_usd = math::token_value_usd(
+            amount,
+            price_usd,
+            -(math::USD_DECIMALS as i32),
+            boost_token.decimals,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+        require!(value_usd > 0, ErrorCode::InvalidBoostValue);
+
+        // Move boost tokens into the boost vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_boost_token_account.to_account_info(),
+            to: ctx.accounts.boost_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        boost_token.total_received = boost_token.total_received
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Calculate boost APY from everything applied so far
+        wexel.boost_value_usd = wexel.boost_value_usd
+            .checked_add(value_usd)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let boost_apy_bp = math::boost_apy_bp(
+            wexel.principal_usd,
+            wexel.boost_value_usd,
+            ctx.accounts.config.boost_target_bp,
+            ctx.accounts.config.max_boost_apy_bp,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update wexel
+        wexel.apy_boost_bp = boost_apy_bp;
+
+        // Emit event
+        emit!(BoostApplied {
+            wexel_id: wexel.id,
+            apy_boost_bp: boost_apy_bp,
+            value_usd,
+            boost_mint: boost_token.mint,
+            amount,
+        });
+
+        // Keep the NFT metadata in step with the boosted APY
+        let config_seeds = &[b"config".as_ref(), &[ctx.accounts.config.bump]];
+        nft::sync_wexel_metadata(
+            nft::MetadataAccounts {
+                mint: &ctx.accounts.wexel_mint.to_account_info(),
+                authority: &ctx.accounts.config.to_account_info(),
+                payer: &ctx.accounts.user.to_account_info(),
+                token_program: &ctx.accounts.token_2022_program.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            &ctx.accounts.wexel,
+            &[&config_seeds[..]],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn mint_wexel_finalize(ctx: Context<MintWexelFinalize>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.claim, ErrorCode::OperationPaused);
+
+        let wexel = &mut ctx.accounts.wexel;
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(clock.unix_timestamp >= wexel.matured_at, ErrorCode::WexelNotMatured);
+
+        // Accrue the final stub up to maturity; accrue rejects finalized wexels
+        if let Some((user_reward, platform_reward)) = wexel.accrue_rewards(
+            clock.unix_timestamp,
+            ctx.accounts.config.collateral_user_share_bp,
+        )? {
+            ctx.accounts.rewards_vault.record_accrual(user_reward, platform_reward)?;
+            emit!(Accrued {
+                wexel_id: wexel.id,
+                reward_usd: user_reward,
+                platform_reward_usd: platform_reward,
+                accrued_at: wexel.last_accrued_at,
+            });
+        }
+
+        // Finalize wexel
+        wexel.is_finalized = true;
+
+        // Emit event
+        emit!(WexelFinalized {
+            wexel_id: wexel.id,
+            finalized_at: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Move a wexel and its NFT to `new_owner`.
+    /// The record is re-created under the new owner's position counter.
+    pub fn transfer_wexel(ctx: Context<TransferWexel>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.marketplace, ErrorCode::OperationPaused);
+
+        let wexel = &ctx.accounts.wexel;
+        let new_owner = ctx.accounts.new_owner.key();
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
+        require!(!wexel.is_locked, ErrorCode::WexelLocked);
+        require!(new_owner != ctx.accounts.owner.key(), ErrorCode::InvalidRecipient);
+
+        // Hand over the NFT
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.wexel_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.new_owner_nft_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_2022_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        // Index the wexel under the new owner
+        let new_owner_positions = &mut ctx.accounts.new_owner_positions;
+        if new_owner_positions.owner == Pubkey::default() {
+            new_owner_positions.owner = new_owner;
+            new_owner_positions.bump = ctx.bumps.new_owner_positions;
+        }
+        let owner_index = new_owner_positions.wexel_count;
+        new_owner_positions.wexel_count = owner_index
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        ctx.accounts.new_wexel.set_inner(Wexel {
+            owner: new_owner,
+            owner_index,
+            ..Wexel::clone(wexel)
+        });
+
+        // Emit event
+        emit!(WexelTransferred {
+            wexel_id,
+            from: ctx.accounts.owner.key(),
+            to: new_owner,
+            wexel: ctx.accounts.new_wexel.key(),
+            transferred_at: clock.unix_timestamp,
+        });
+
+        // Old record is closed to the previous owner on exit
+        Ok(())
+    }
+
+    /// Set the account that receives platform fees and rewards (admin or treasurer)
+    pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == config.admin || ctx.accounts.treasurer_role.is_some(),
+            ErrorCode::Unauthorized
+        );
+
+        config.treasury = treasury;
+
+        emit!(TreasuryUpdated {
+            treasury,
+            updated_by: authority,
+        });
+
+        Ok(())
+    }
+
+    /// List a wexel for `price` base units of `payment_mint`, escrowing its NFT
+    pub fn list_wexel(ctx: Context<ListWexel>, wexel_id: u64, price: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.marketplace, ErrorCode::OperationPaused);
+
+        let wexel = &ctx.accounts.wexel;
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(!wexel.is_locked, ErrorCode::WexelLocked);
+        require!(price > 0, ErrorCode::InvalidPrice);
+        require!(
+            ctx.accounts.config.accepted_mints.contains(&ctx.accounts.payment_mint.key()),
+            ErrorCode::MintNotAccepted
+        );
+
+        // Escrow the NFT
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.seller_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.escrow_nft_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_2022_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        let listing = &mut ctx.accounts.listing;
+        listing.wexel_id = wexel_id;
+        listing.seller = ctx.accounts.seller.key();
+        listing.wexel = wexel.key();
+        listing.nft_mint = wexel.nft_mint;
+        listing.payment_mint = ctx.accounts.payment_mint.key();
+        listing.price = price;
+        listing.created_at = clock.unix_timestamp;
+        listing.bump = ctx.bumps.listing;
+        listing.auction = None;
+
+        emit!(WexelListed {
+            wexel_id,
+            seller: listing.seller,
+            payment_mint: listing.payment_mint,
+            price,
+            auction: None,
+            listed_at: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// List a wexel by Dutch auction, escrowing its NFT. The price decays
+    /// linearly from `auction.start_price` to `auction.floor_price` between
+    /// `auction.start_time` and `auction.end_time`, then holds at the floor.
+    pub fn list_wexel_dutch(
+        ctx: Context<ListWexel>,
+        wexel_id: u64,
+        auction: DutchAuction,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused.marketplace, ErrorCode::OperationPaused);
+
+        let wexel = &ctx.accounts.wexel;
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(!wexel.is_locked, ErrorCode::WexelLocked);
+
+        // Validate auction
+        require!(auction.floor_price > 0, ErrorCode::InvalidPrice);
+        require!(auction.start_price >= auction.floor_price, ErrorCode::InvalidAuction);
+        require!(auction.end_time > auction.start_time, ErrorCode::InvalidAuction);
+        require!(auction.end_time > clock.unix_timestamp, ErrorCode::InvalidAuction);
+        require!(
+            ctx.accounts.config.accepted_mints.contains(&ctx.accounts.payment_mint.key()),
+            ErrorCode::MintNotAccepted
+        );
+
+        // Escrow the NFT
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.seller_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.escrow_nft_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_2022_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        let listing = &mut ctx.accounts.listing;
+        listing.wexel_id = wexel_id;
+        listing.seller = ctx.accounts.seller.key();
+        listing.wexel = wexel.key();
+        listing.nft_mint = wexel.nft_mint;
+        listing.payment_mint = ctx.accounts.payment_mint.key();
+        listing.price = auction.start_price;
+        listing.created_at = clock.unix_timestamp;
+        listing.bump = ctx.bumps.listing;
+        listing.auction = Some(auction);
+
+        emit!(WexelListed {
+            wexel_id,
+            seller: listing.seller,
+            payment_mint: listing.payment_mint,
+            price: auction.start_price,
+            auction: Some(auction),
+            listed_at: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a listing and return the NFT to the seller
+    pub fn delist_wexel(ctx: Context<DelistWexel>, wexel_id: u64) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        let wexel_id_bytes = wexel_id.to_le_bytes();
+        let listing_seeds = &[b"listing".as_ref(), wexel_id_bytes.as_ref(), &[listing.bump]];
+        let listing_signer = &[&listing_seeds[..]];
+
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.escrow_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.seller_nft_account.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            listing_signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        let cpi_accounts = token_interface::CloseAccount {
+            account: ctx.accounts.escrow_nft_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            listing_signer,
+        );
+        token_interface::close_account(cpi_ctx)?;
+
+        emit!(WexelDelisted {
+            wexel_id,
+            seller: listing.seller,
+        });
+
+        // Listing is closed to the seller on exit
+        Ok(())
+    }
+
+    /// Buy a listed wexel for at most `max_price`. Payment goes to the seller
+    /// minus the platform fee, the NFT to the buyer, and the record is
+    /// re-created under the buyer. Auction prices are taken at execution time.
+    pub fn buy_wexel(ctx: Context<BuyWexel>, wexel_id: u64, max_price: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.marketplace, ErrorCode::OperationPaused);
+
+        let listing = &ctx.accounts.listing;
+        let wexel = &ctx.accounts.wexel;
+        let buyer = ctx.accounts.buyer.key();
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(!wexel.is_locked, ErrorCode::WexelLocked);
+        require!(buyer != listing.seller, ErrorCode::InvalidRecipient);
+
+        // Price the sale
+        let price = match listing.auction {
+            Some(auction) => {
+                require!(clock.unix_timestamp >= auction.start_time, ErrorCode::AuctionNotStarted);
+                math::dutch_auction_price(
+                    auction.start_price,
+                    auction.floor_price,
+                    auction.start_time,
+                    auction.end_time,
+                    clock.unix_timestamp,
+                )
+                .ok_or(ErrorCode::MathOverflow)?
+            }
+            None => listing.price,
+        };
+        require!(price <= max_price, ErrorCode::SlippageExceeded);
+
+        // Settle payment: platform fee to the treasury, the rest to the seller
+        let fee = math::bps_of(price, ctx.accounts.config.marketplace_fee_bp)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let seller_proceeds = price.checked_sub(fee).ok_or(ErrorCode::MathOverflow)?;
+
+        if fee > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.buyer_payment_account.to_account_info(),
+                to: ctx.accounts.treasury_payment_account.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::transfer(cpi_ctx, fee)?;
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_payment_account.to_account_info(),
+            to: ctx.accounts.seller_payment_account.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, seller_proceeds)?;
+
+        // Release the NFT from escrow to the buyer
+        let wexel_id_bytes = wexel_id.to_le_bytes();
+        let listing_seeds = &[b"listing".as_ref(), wexel_id_bytes.as_ref(), &[listing.bump]];
+        let listing_signer = &[&listing_seeds[..]];
+
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.escrow_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.buyer_nft_account.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            listing_signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        let cpi_accounts = token_interface::CloseAccount {
+            account: ctx.accounts.escrow_nft_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            listing_signer,
+        );
+        token_interface::close_account(cpi_ctx)?;
+
+        // Index the wexel under the buyer
+        let buyer_positions = &mut ctx.accounts.buyer_positions;
+        if buyer_positions.owner == Pubkey::default() {
+            buyer_positions.owner = buyer;
+            buyer_positions.bump = ctx.bumps.buyer_positions;
+        }
+        let owner_index = buyer_positions.wexel_count;
+        buyer_positions.wexel_count = owner_index
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        ctx.accounts.new_wexel.set_inner(Wexel {
+            owner: buyer,
+            owner_index,
+            ..Wexel::clone(wexel)
+        });
+
+        emit!(WexelSold {
+            wexel_id,
+            seller: listing.seller,
+            buyer,
+            payment_mint: listing.payment_mint,
+            price,
+            fee,
+            sold_at: clock.unix_timestamp,
+        });
+
+        // Listing and old record are closed to the seller on exit
+        Ok(())
+    }
+
+    /// Escrow `amount` of `payment_mint` as a bid for wexel `wexel_id`,
+    /// or for any wexel matching `criteria` when `wexel_id` is `None`
+    pub fn make_offer(
+        ctx: Context<MakeOffer>,
+        offer_id: u64,
+        wexel_id: Option<u64>,
+        criteria: OfferCriteria,
+        amount: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.paused.marketplace, ErrorCode::OperationPaused);
+
+        let clock = Clock::get()?;
+
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(expires_at > clock.unix_timestamp, ErrorCode::InvalidExpiry);
+        require!(
+            ctx.accounts.config.accepted_mints.contains(&ctx.accounts.payment_mint.key()),
+            ErrorCode::MintNotAccepted
+        );
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.bidder_payment_account.to_account_info(),
+            to: ctx.accounts.offer_escrow.to_account_info(),
+            authority: ctx.accounts.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.bidder = ctx.accounts.bidder.key();
+        offer.offer_id = offer_id;
+        offer.wexel_id = wexel_id;
+        offer.criteria = criteria;
+        offer.payment_mint = ctx.accounts.payment_mint.key();
+        offer.amount = amount;
+        offer.created_at = clock.unix_timestamp;
+        offer.expires_at = expires_at;
+        offer.bump = ctx.bumps.offer;
+        offer.escrow_bump = ctx.bumps.offer_escrow;
+
+        emit!(OfferMade {
+            offer: offer.key(),
+            bidder: offer.bidder,
+            wexel_id,
+            criteria,
+            payment_mint: offer.payment_mint,
+            amount,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw an offer. The bidder may cancel at any time; anyone may
+    /// refund it to the bidder once it has expired.
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let clock = Clock::get()?;
+
+        let expired = clock.unix_timestamp >= offer.expires_at;
+        require!(
+            expired || ctx.accounts.caller.key() == offer.bidder,
+            ErrorCode::OfferNotExpired
+        );
+
+        let offer_id_bytes = offer.offer_id.to_le_bytes();
+        let offer_seeds = &[
+            b"offer".as_ref(),
+            offer.bidder.as_ref(),
+            offer_id_bytes.as_ref(),
+            &[offer.bump],
+        ];
+        let offer_signer = &[&offer_seeds[..]];
+
+        let refunded = ctx.accounts.offer_escrow.amount;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.offer_escrow.to_account_info(),
+            to: ctx.accounts.bidder_payment_account.to_account_info(),
+            authority: offer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            offer_signer,
+        );
+        token::transfer(cpi_ctx, refunded)?;
+
+        let cpi_accounts = token::CloseAccount {
+            account: ctx.accounts.offer_escrow.to_account_info(),
+            destination: ctx.accounts.bidder.to_account_info(),
+            authority: offer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            offer_signer,
+        );
+        token::close_account(cpi_ctx)?;
+
+        emit!(OfferCancelled {
+            offer: offer.key(),
+            bidder: offer.bidder,
+            refunded,
+            expired,
+        });
+
+        // Offer is closed to the bidder on exit
+        Ok(())
+    }
+
+    /// Sell wexel `wexel_id` into a matching offer. The holder receives the bid
+    /// minus the platform fee and the bidder receives the NFT and record.
+    pub fn accept_offer(ctx: Context<AcceptOffer>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.marketplace, ErrorCode::OperationPaused);
+
+        let offer = &ctx.accounts.offer;
+        let wexel = &ctx.accounts.wexel;
+        let bidder = offer.bidder;
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(!wexel.is_locked, ErrorCode::WexelLocked);
+        require!(ctx.accounts.seller.key() != bidder, ErrorCode::InvalidRecipient);
+
+        // Validate offer
+        require!(clock.unix_timestamp < offer.expires_at, ErrorCode::OfferExpired);
+        match offer.wexel_id {
+            Some(id) => require!(id == wexel_id, ErrorCode::OfferCriteriaNotMet),
+            None => {
+                let criteria = &offer.criteria;
+                let remaining_days = wexel.matured_at
+                    .saturating_sub(clock.unix_timestamp)
+                    .max(0) as u64
+                    / SECONDS_PER_DAY;
+                let total_apy_bp = wexel.apy_bp as u32 + wexel.apy_boost_bp as u32;
+                require!(
+                    (criteria.pool_id.is_none() || criteria.pool_id == Some(wexel.pool_id))
+                        && remaining_days >= criteria.min_remaining_days as u64
+                        && total_apy_bp >= criteria.min_apy_bp as u32
+                        && wexel.principal_usd >= criteria.min_principal_usd,
+                    ErrorCode::OfferCriteriaNotMet
+                );
+            }
+        }
+
+        // Hand over the NFT
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.seller_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            to: ctx.accounts.bidder_nft_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_2022_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        // Pay out the escrowed bid: platform fee to the treasury, the rest to the seller
+        let amount = offer.amount;
+        let fee = math::bps_of(amount, ctx.accounts.config.marketplace_fee_bp)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let seller_proceeds = amount.checked_sub(fee).ok_or(ErrorCode::MathOverflow)?;
+
+        let offer_id_bytes = offer.offer_id.to_le_bytes();
+        let offer_seeds = &[
+            b"offer".as_ref(),
+            bidder.as_ref(),
+            offer_id_bytes.as_ref(),
+            &[offer.bump],
+        ];
+        let offer_signer = &[&offer_seeds[..]];
+
+        if fee > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.offer_escrow.to_account_info(),
+                to: ctx.accounts.treasury_payment_account.to_account_info(),
+                authority: offer.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                offer_signer,
+            );
+            token::transfer(cpi_ctx, fee)?;
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.offer_escrow.to_account_info(),
+            to: ctx.accounts.seller_payment_account.to_account_info(),
+            authority: offer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            offer_signer,
+        );
+        token::transfer(cpi_ctx, seller_proceeds)?;
+
+        let cpi_accounts = token::CloseAccount {
+            account: ctx.accounts.offer_escrow.to_account_info(),
+            destination: ctx.accounts.bidder.to_account_info(),
+            authority: offer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            offer_signer,
+        );
+        token::close_account(cpi_ctx)?;
+
+        // Index the wexel under the bidder
+        let bidder_positions = &mut ctx.accounts.bidder_positions;
+        if bidder_positions.owner == Pubkey::default() {
+            bidder_positions.owner = bidder;
+            bidder_positions.bump = ctx.bumps.bidder_positions;
+        }
+        let owner_index = bidder_positions.wexel_count;
+        bidder_positions.wexel_count = owner_index
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        ctx.accounts.new_wexel.set_inner(Wexel {
+            owner: bidder,
+            owner_index,
+            ..Wexel::clone(wexel)
+        });
+
+        emit!(OfferAccepted {
+            offer: offer.key(),
+            wexel_id,
+            seller: ctx.accounts.seller.key(),
+            bidder,
+            amount,
+            fee,
+            accepted_at: clock.unix_timestamp,
+        });
+
+        // Offer is closed to the bidder and the old record to the seller on exit
+        Ok(())
+    }
+
+    pub fn accrue(ctx: Context<Accrue>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.claim, ErrorCode::OperationPaused);
+
+        let wexel = &mut ctx.accounts.wexel;
+        let rewards_vault = &mut ctx.accounts.rewards_vault;
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+
+        // H-3 fix: Ensure at least 1 day has passed since last accrual
+        // (the final stub up to maturity may be accrued at any time after it)
+        let time_since_last_accrual = clock.unix_timestamp - wexel.last_accrued_at;
+        require!(
+            time_since_last_accrual >= SECONDS_PER_DAY as i64
+                || clock.unix_timestamp >= wexel.matured_at,
+            ErrorCode::TooEarlyToAccrue
+        );
+
+        let Some((user_reward, platform_reward)) = wexel.accrue_rewards(
+            clock.unix_timestamp,
+            ctx.accounts.config.collateral_user_share_bp,
+        )? else {
+            return err!(ErrorCode::NothingToAccrue);
+        };
+        rewards_vault.record_accrual(user_reward, platform_reward)?;
+
+        // Emit event
+        emit!(Accrued {
+            wexel_id: wexel.id,
+            reward_usd: user_reward,
+            platform_reward_usd: platform_reward,
+            accrued_at: wexel.last_accrued_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.claim, ErrorCode::OperationPaused);
+
+        let wexel = &mut ctx.accounts.wexel;
+        let rewards_vault = &mut ctx.accounts.rewards_vault;
+
+        // H-1 fix: Reentrancy guard
+        require!(!wexel.is_locked, ErrorCode::ReentrancyDetected);
+        wexel.is_locked = true;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(wexel.total_rewards > 0, ErrorCode::InvalidAmount);
+
+        // Calculate claimable amount
+        let claimable_amount = wexel.total_rewards - wexel.claimed_rewards;
+        require!(claimable_amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.rewards_token_vault.amount >= claimable_amount,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        // Pay out from the rewards token vault, signed by the vault PDA
+        let pool_id_bytes = rewards_vault.pool_id.to_le_bytes();
+        let seeds = &[
+            b"rewards_vault".as_ref(),
+            pool_id_bytes.as_ref(),
+            &[rewards_vault.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.rewards_token_vault.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: rewards_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, claimable_amount)?;
+
+        // Update wexel
+        wexel.claimed_rewards = wexel.claimed_rewards
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update rewards vault
+        rewards_vault.distributed_rewards = rewards_vault.distributed_rewards
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Emit event
+        emit!(Claimed {
+            wexel_id: wexel.id,
+            to: ctx.accounts.user.key(),
+            amount_usd: claimable_amount,
+        });
+
+        // H-1 fix: Release reentrancy lock
+        wexel.is_locked = false;
+
+        Ok(())
+    }
+
+    /// Pay the platform's accrued share of a pool's rewards to the treasury; anyone may call
+    pub fn claim_platform_rewards(ctx: Context<ClaimPlatformRewards>, pool_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.claim, ErrorCode::OperationPaused);
+
+        let rewards_vault = &mut ctx.accounts.rewards_vault;
+
+        let claimable_amount = rewards_vault.platform_rewards
+            .checked_sub(rewards_vault.platform_claimed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(claimable_amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.rewards_token_vault.amount >= claimable_amount,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        // Pay out from the rewards token vault, signed by the vault PDA
+        let pool_id_bytes = pool_id.to_le_bytes();
+        let seeds = &[
+            b"rewards_vault".as_ref(),
+            pool_id_bytes.as_ref(),
+            &[rewards_vault.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.rewards_token_vault.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: rewards_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token::transfer(cpi_ctx, claimable_amount)?;
+
+        rewards_vault.platform_claimed = rewards_vault.platform_claimed
+            .checked_add(claimable_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PlatformRewardsClaimed {
+            pool_id,
+            to: ctx.accounts.treasury_token_account.key(),
+            amount_usd: claimable_amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn collateralize(ctx: Context<Collateralize>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.collateralize, ErrorCode::OperationPaused);
+
+        let wexel = &mut ctx.accounts.wexel;
+        let collateral_position = &mut ctx.accounts.collateral_position;
+        let clock = Clock::get()?;
+
+        // H-1 fix: Reentrancy guard
+        require!(!wexel.is_locked, ErrorCode::ReentrancyDetected);
+        wexel.is_locked = true;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(!wexel.is_collateralized, ErrorCode::WexelAlreadyCollateralized);
+        require!(!wexel.is_finalized, ErrorCode::WexelAlreadyFinalized);
+        require!(
+            ctx.accounts.wexel_nft_account.owner == ctx.accounts.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Calculate loan amount
+        let ltv_bp = ctx.accounts.config.ltv_bp;
+        let loan_usd = math::bps_of(wexel.principal_usd, ltv_bp).ok_or(ErrorCode::MathOverflow)?;
+        require!(loan_usd > 0, ErrorCode::InvalidLoanAmount);
+        require!(
+            ctx.accounts.pool_vault.amount >= loan_usd,
+            ErrorCode::InsufficientLiquidity
+        );
+
+        // Disburse the loan from the pool vault, signed by the pool PDA
+        let pool = &mut ctx.accounts.pool;
+        let pool_id_bytes = pool.id.to_le_bytes();
+        let pool_seeds = &[b"pool".as_ref(), pool_id_bytes.as_ref(), &[pool.bump]];
+        let pool_signer = &[&pool_seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool_vault.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            pool_signer,
+        );
+        token::transfer(cpi_ctx, loan_usd)?;
+
+        pool.total_loans = pool.total_loans
+            .checked_add(loan_usd)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Initialize collateral position
+        collateral_position.wexel_id = wexel_id;
+        collateral_position.owner = ctx.accounts.user.key();
+        collateral_position.loan_usd = loan_usd;
+        collateral_position.ltv_bp = ltv_bp;
+        collateral_position.created_at = clock.unix_timestamp;
+        collateral_position.is_repaid = false;
+        collateral_position.principal_usd = loan_usd;
+        collateral_position.interest_usd = 0;
+        collateral_position.interest_remainder = 0;
+        collateral_position.borrow_rate_bp = pool.borrow_rate_bp;
+        collateral_position.last_interest_at = clock.unix_timestamp;
+
+        // Update wexel
+        wexel.is_collateralized = true;
+        wexel.collateralized_at = clock.unix_timestamp;
+
+        // Emit event
+        emit!(Collateralized {
+            wexel_id: wexel.id,
+            loan_usd,
+            ltv_bp,
+            borrow_rate_bp: collateral_position.borrow_rate_bp,
+        });
+
+        // H-1 fix: Release reentrancy lock
+        wexel.is_locked = false;
+
+        // Freeze the NFT so the wexel cannot change hands while it backs a loan
+        let config_seeds = &[b"config".as_ref(), &[ctx.accounts.config.bump]];
+        let config_signer = &[&config_seeds[..]];
+        let cpi_accounts = token_interface::FreezeAccount {
+            account: ctx.accounts.wexel_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            config_signer,
+        );
+        token_interface::freeze_account(cpi_ctx)?;
+
+        Ok(())
+    }
+
+    /// Repay up to `repay_amount` of a loan, interest first. Once the debt
+    /// reaches zero the wexel is released and the position closed.
+    pub fn repay_loan(ctx: Context<RepayLoan>, wexel_id: u64, repay_amount: u64) -> Result<()> {
+        let wexel = &mut ctx.accounts.wexel;
+        let collateral_position = &mut ctx.accounts.collateral_position;
+        let clock = Clock::get()?;
+
+        // H-1 fix: Reentrancy guard
+        require!(!wexel.is_locked, ErrorCode::ReentrancyDetected);
+        wexel.is_locked = true;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(wexel.is_collateralized, ErrorCode::WexelNotCollateralized);
+        require!(!collateral_position.is_repaid, ErrorCode::InvalidRepaymentAmount);
+        require!(
+            ctx.accounts.wexel_nft_account.owner == ctx.accounts.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Validate repayment amount
+        require!(repay_amount > 0, ErrorCode::InvalidAmount);
+
+        // Bring interest up to date and apply the payment to interest first
+        collateral_position.accrue_interest(clock.unix_timestamp)?;
+        let debt_usd = collateral_position.debt_usd().ok_or(ErrorCode::MathOverflow)?;
+        let repaid_amount = repay_amount.min(debt_usd);
+        let interest_paid = repaid_amount.min(collateral_position.interest_usd);
+        let principal_paid = repaid_amount - interest_paid;
+
+        // Pull the payment back into the pool vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.pool_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, repaid_amount)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_loans = pool.total_loans
+            .checked_sub(principal_paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update collateral position
+        collateral_position.interest_usd -= interest_paid;
+        collateral_position.principal_usd -= principal_paid;
+        let is_closed = collateral_position.principal_usd == 0 && collateral_position.interest_usd == 0;
+        collateral_position.is_repaid = is_closed;
+
+        if is_closed {
+            // Update wexel, keeping the pledged time not yet accrued for the yield split
+            let pledge_end = clock.unix_timestamp.min(wexel.matured_at);
+            let pledged_from = wexel.collateralized_at.max(wexel.last_accrued_at);
+            wexel.pending_collateral_seconds = wexel.pending_collateral_seconds
+                .checked_add(pledge_end.saturating_sub(pledged_from).max(0) as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+            wexel.is_collateralized = false;
+            wexel.collateralized_at = 0;
+        }
+
+        // Emit event
+        emit!(LoanRepaid {
+            wexel_id: wexel.id,
+            repaid_amount,
+            interest_paid,
+            remaining_principal_usd: collateral_position.principal_usd,
+            remaining_interest_usd: collateral_position.interest_usd,
+            is_closed,
+        });
+
+        // H-1 fix: Release reentrancy lock
+        wexel.is_locked = false;
+
+        if !is_closed {
+            return Ok(());
+        }
+
+        // The NFT may change hands again
+        let config_seeds = &[b"config".as_ref(), &[ctx.accounts.config.bump]];
+        let config_signer = &[&config_seeds[..]];
+        let cpi_accounts = token_interface::ThawAccount {
+            account: ctx.accounts.wexel_nft_account.to_account_info(),
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            config_signer,
+        );
+        token_interface::thaw_account(cpi_ctx)?;
+
+        // Close the position so the wexel can be pledged again
+        ctx.accounts.collateral_position.close(ctx.accounts.user.to_account_info())?;
+
+        Ok(())
+    }
+
+    /// Redeem a matured wexel for its principal and unclaimed rewards. An open
+    /// loan is settled first: the debt is netted out of principal, then rewards,
+    /// and repaid to the pool vault; the owner receives the rest.
+    pub fn redeem(ctx: Context<Redeem>, wexel_id: u64) -> Result<()> {
+        require!(!ctx.accounts.config.paused.claim, ErrorCode::OperationPaused);
+
+        let wexel = &mut ctx.accounts.wexel;
+        let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+
+        // Validate wexel
+        require!(wexel.id == wexel_id, ErrorCode::WexelNotFound);
+        require!(wexel.is_finalized, ErrorCode::WexelNotFinalized);
+        require!(clock.unix_timestamp >= wexel.matured_at, ErrorCode::WexelNotMatured);
+
+        // Yield still owed up to maturity is booked before the loan is settled,
+        // while the wexel still counts as pledged, so the platform gets its share
+        if let Some((user_reward, platform_reward)) = wexel.accrue_rewards(
+            clock.unix_timestamp,
+            ctx.accounts.config.collateral_user_share_bp,
+        )? {
+            let Some(rewards_vault) = ctx.accounts.rewards_vault.as_mut() else {
+                return err!(ErrorCode::InsufficientVaultBalance);
+            };
+            rewards_vault.record_accrual(user_reward, platform_reward)?;
+            emit!(Accrued {
+                wexel_id: wexel.id,
+                reward_usd: user_reward,
+                platform_reward_usd: platform_reward,
+                accrued_at: wexel.last_accrued_at,
+            });
+        }
+
+        let unclaimed_rewards = wexel.total_rewards
+            .checked_sub(wexel.claimed_rewards)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Settle an open loan: principal covers the debt first, then rewards
+        let mut debt_from_principal = 0;
+        let mut debt_from_rewards = 0;
+        if wexel.is_collateralized {
+            let Some(collateral_position) = ctx.accounts.collateral_position.as_mut() else {
+                return err!(ErrorCode::WexelAlreadyCollateralized);
+            };
+            require!(!collateral_position.is_repaid, ErrorCode::InvalidRepaymentAmount);
+
+            collateral_position.accrue_interest(clock.unix_timestamp)?;
+            let debt_usd = collateral_position.debt_usd().ok_or(ErrorCode::MathOverflow)?;
+            debt_from_principal = debt_usd.min(wexel.principal_usd);
+            debt_from_rewards = (debt_usd - debt_from_principal).min(unclaimed_rewards);
+            let shortfall_usd = debt_usd - debt_from_principal - debt_from_rewards;
+
+            // The whole loan leaves the books; any shortfall is written off
+            pool.total_loans = pool.total_loans
+                .checked_sub(collateral_position.principal_usd)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            emit!(LoanSettledAtMaturity {
+                wexel_id,
+                principal_usd: collateral_position.principal_usd,
+                interest_usd: collateral_position.interest_usd,
+                repaid_from_principal: debt_from_principal,
+                repaid_from_rewards: debt_from_rewards,
+                shortfall_usd,
+                settled_at: clock.unix_timestamp,
+            });
+
+            collateral_position.principal_usd = 0;
+            collateral_position.interest_usd = 0;
+            collateral_position.is_repaid = true;
+            wexel.is_collateralized = false;
+        }
+
+        let principal_to_owner = wexel.principal_usd - debt_from_principal;
+        let rewards_to_owner = unclaimed_rewards - debt_from_rewards;
+        require!(
+            ctx.accounts.pool_vault.amount >= principal_to_owner,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        // Return principal from the pool vault, signed by the pool PDA;
+        // the part that repaid the loan stays in the vault
+        if principal_to_owner > 0 {
+            let pool_id_bytes = pool.id.to_le_bytes();
+            let pool_seeds = &[b"pool".as_ref(), pool_id_bytes.as_ref(), &[pool.bump]];
+            let pool_signer = &[&pool_seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                pool_signer,
+            );
+            token::transfer(cpi_ctx, principal_to_owner)?;
+        }
+
+        // Pay out any rewards accrued but not yet claimed, less what repaid the loan
+        if unclaimed_rewards > 0 {
+            let (Some(rewards_vault), Some(rewards_token_vault)) = (
+                ctx.accounts.rewards_vault.as_mut(),
+                ctx.accounts.rewards_token_vault.as_ref(),
+            ) else {
+                return err!(ErrorCode::InsufficientVaultBalance);
+            };
+            require_keys_eq!(
+                rewards_token_vault.key(),
+                Pubkey::create_program_address(
+                    &[
+                        b"rewards_token_vault",
+                        rewards_vault.key().as_ref(),
+                        &[rewards_vault.token_vault_bump],
+                    ],
+                    &crate::ID
+                ).map_err(|_| ErrorCode::InvalidVault)?,
+                ErrorCode::InvalidVault
+            );
+            require!(
+                rewards_token_vault.amount >= unclaimed_rewards,
+                ErrorCode::InsufficientVaultBalance
+            );
+
+            let pool_id_bytes = rewards_vault.pool_id.to_le_bytes();
+            let seeds = &[
+                b"rewards_vault".as_ref(),
+                pool_id_bytes.as_ref(),
+                &[rewards_vault.bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            if debt_from_rewards > 0 {
+                let cpi_accounts = Transfer {
+                    from: rewards_token_vault.to_account_info(),
+                    to: ctx.accounts.pool_vault.to_account_info(),
+                    authority: rewards_vault.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                );
+                token::transfer(cpi_ctx, debt_from_rewards)?;
+            }
+
+            if rewards_to_owner > 0 {
+                let cpi_accounts = Transfer {
+                    from: rewards_token_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: rewards_vault.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                );
+                token::transfer(cpi_ctx, rewards_to_owner)?;
+            }
+
+            rewards_vault.distributed_rewards = rewards_vault.distributed_rewards
+                .checked_add(unclaimed_rewards)
+                .ok_or(ErrorCode::MathOverflow)?;
+            wexel.claimed_rewards = wexel.total_rewards;
+        }
+
+        // Update pool
+        pool.total_deposits = pool.total_deposits
+            .checked_sub(wexel.principal_usd)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Emit event
+        emit!(Redeemed {
+            wexel_id: wexel.id,
+            principal_usd: principal_to_owner,
+            rewards_usd: rewards_to_owner,
+            redeemed_at: clock.unix_timestamp,
+        });
+
+        let config_seeds = &[b"config".as_ref(), &[ctx.accounts.config.bump]];
+        let config_signer = &[&config_seeds[..]];
+
+        // A settled loan releases the NFT and closes the position
+        if let Some(collateral_position) = ctx.accounts.collateral_position.as_ref() {
+            if ctx.accounts.wexel_nft_account.is_frozen() {
+                let cpi_accounts = token_interface::ThawAccount {
+                    account: ctx.accounts.wexel_nft_account.to_account_info(),
+                    mint: ctx.accounts.wexel_mint.to_account_info(),
+                    authority: ctx.accounts.config.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_2022_program.to_account_info(),
+                    cpi_accounts,
+                    config_signer,
+                );
+                token_interface::thaw_account(cpi_ctx)?;
+            }
+            collateral_position.close(ctx.accounts.user.to_account_info())?;
+        }
+
+        // Burn the wexel NFT and return the rent of its token account and mint
+        let cpi_accounts = token_interface::Burn {
+            mint: ctx.accounts.wexel_mint.to_account_info(),
+            from: ctx.accounts.wexel_nft_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_2022_program.to_account_info(), cpi_accounts);
+        token_interface::burn(cpi_ctx, 1)?;
+
+        let cpi_accounts = token_interface::CloseAccount {
+            account: ctx.accounts.wexel_nft_account.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_2022_program.to_account_info(), cpi_accounts);
+        token_interface::close_account(cpi_ctx)?;
+
+        let cpi_accounts = token_interface::CloseAccount {
+            account: ctx.accounts.wexel_mint.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            cpi_accounts,
+            config_signer,
+        );
+        token_interface::close_account(cpi_ctx)?;
+
+        // Wexel account is closed to the owner on exit, so it can only be redeemed once
+
+        Ok(())
+    }
+}
+
+// Account contexts
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 32 + 8 + 1 + 8 + 32 + 2 + 2 + 5 + (4 + 32 * 8) + 32 + 32 + 32 + 2 + 8 + 2 + 2 + 8 + 8, // GlobalConfig::LEN
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub usdt_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 8 + 8 + 8 + 8 + 32 + 1 + 1 + (4 + 8 * (1 + 2)) + 8 + 8 + 1 + 2, // Pool::LEN
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = usdt_mint,
+        token::authority = pool,
+        seeds = [b"pool_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct SetPoolActive<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializeRewardsVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        constraint = usdt_mint.key() == pool.usdt_mint @ solana_contracts::ErrorCode::InvalidMint
+    )]
+    pub usdt_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8, // RewardsVault::LEN
+        seeds = [b"rewards_vault", pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub rewards_vault: Account<'info, RewardsVault>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = usdt_mint,
+        token::authority = rewards_vault,
+        seeds = [b"rewards_token_vault", rewards_vault.key().as_ref()],
+        bump
+    )]
+    pub rewards_token_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct FundRewardsVault<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Only required when the signer is not the admin
+    #[account(
+        seeds = [b"role", [Role::Treasurer as u8].as_ref(), authority.key().as_ref()],
+        bump = treasurer_role.bump
+    )]
+    pub treasurer_role: Option<Account<'info, RoleAssignment>>,
+    #[account(
+        mut,
+        seeds = [b"rewards_vault", pool_id.to_le_bytes().as_ref()],
+        bump = rewards_vault.bump
+    )]
+    pub rewards_vault: Account<'info, RewardsVault>,
+    #[account(
+        mut,
+        seeds = [b"rewards_token_vault", rewards_vault.key().as_ref()],
+        bump = rewards_vault.token_vault_bump
+    )]
+    pub rewards_token_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = authority_token_account.mint == rewards_token_vault.mint @ solana_contracts::ErrorCode::InvalidMint
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePriceOracle<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 32 + (4 + 32 * oracle::MAX_PRICE_SOURCES) + 8 + 2 + 8 + 8 + 1, // PriceOracle::LEN
+        seeds = [b"price_oracle", mint.key().as_ref()],
+        bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceOracle<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [b"price_oracle", price_oracle.mint.as_ref()],
+        bump = price_oracle.bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+}
+
+#[derive(Accounts)]
+pub struct SetFallbackPrice<'info> {
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Only required when the signer is not the oracle authority
+    #[account(
+        seeds = [b"role", [Role::Oracle as u8].as_ref(), oracle.key().as_ref()],
+        bump = oracle_role.bump
+    )]
+    pub oracle_role: Option<Account<'info, RoleAssignment>>,
+    #[account(
+        mut,
+        seeds = [b"price_oracle", price_oracle.mint.as_ref()],
+        bump = price_oracle.bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+}
+
+#[derive(Accounts)]
+pub struct AddBoostToken<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub boost_mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"price_oracle", boost_mint.key().as_ref()],
+        bump = price_oracle.bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 32 + 1 + 1 + 8 + 1 + 1, // BoostToken::LEN
+        seeds = [b"boost_token", boost_mint.key().as_ref()],
+        bump
+    )]
+    pub boost_token: Account<'info, BoostToken>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = boost_mint,
+        token::authority = boost_token,
+        seeds = [b"boost_vault", boost_mint.key().as_ref()],
+        bump
+    )]
+    pub boost_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBoostTokenActive<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [b"boost_token", boost_token.mint.as_ref()],
+        bump = boost_token.bump
+    )]
+    pub boost_token: Account<'info, BoostToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64, principal_usd: u64, lock_period_months: u8)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"pool_vault", pool.key().as_ref()],
+        bump = pool.vault_bump
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.usdt_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 8 + 1, // UserPositions::LEN
+        seeds = [b"user_positions", user.key().as_ref()],
+        bump
+    )]
+    pub user_positions: Account<'info, UserPositions>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 8 + 32 + 8 + 2 + 2 + 1 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 32 + 8 + 8, // Wexel::LEN
+        seeds = [b"wexel", user.key().as_ref(), user_positions.wexel_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub wexel: Account<'info, Wexel>,
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"wexel_mint", wexel.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = config,
+        mint::freeze_authority = config,
+        mint::token_program = token_2022_program,
+        extensions::metadata_pointer::authority = config,
+        extensions::metadata_pointer::metadata_address = wexel_mint,
+        extensions::close_authority::authority = config
+    )]
+    pub wexel_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_2022_program
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wexel_id: u64, amount: u64)]
+pub struct ApplyBoost<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub wexel: Account<'info, Wexel>,
+    #[account(
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"boost_token", boost_token.mint.as_ref()],
+        bump = boost_token.bump
+    )]
+    pub boost_token: Account<'info, BoostToken>,
+    #[account(
+        mut,
+        seeds = [b"boost_vault", boost_token.mint.as_ref()],
+        bump = boost_token.vault_bump
+    )]
+    pub boost_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_boost_token_account.mint == boost_token.mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = user_boost_token_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub user_boost_token_account: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"price_oracle", boost_token.mint.as_ref()],
+        bump = price_oracle.bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    /// CHECK: the wexel's NFT mint; its metadata is rewritten through Token-2022
+    #[account(mut, address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wexel_id: u64)]
+pub struct MintWexelFinalize<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub wexel: Account<'info, Wexel>,
+    #[account(
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"rewards_vault", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = rewards_vault.bump
+    )]
+    pub rewards_vault: Account<'info, RewardsVault>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wexel_id: u64)]
+pub struct TransferWexel<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: any wallet may receive a wexel
+    pub new_owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = owner
+    )]
+    pub wexel: Account<'info, Wexel>,
+    #[account(
+        mut,
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == owner.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = new_owner,
+        associated_token::token_program = token_2022_program
+    )]
+    pub new_owner_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + 32 + 8 + 1, // UserPositions::LEN
+        seeds = [b"user_positions", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_owner_positions: Account<'info, UserPositions>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 8 + 32 + 8 + 2 + 2 + 1 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 32 + 8 + 8, // Wexel::LEN
+        seeds = [b"wexel", new_owner.key().as_ref(), new_owner_positions.wexel_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_wexel: Account<'info, Wexel>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Only required when the signer is not the admin
+    #[account(
+        seeds = [b"role", [Role::Pauser as u8].as_ref(), authority.key().as_ref()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleAssignment>>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Only required when the signer is not the admin
+    #[account(
+        seeds = [b"role", [Role::Treasurer as u8].as_ref(), authority.key().as_ref()],
+        bump = treasurer_role.bump
+    )]
+    pub treasurer_role: Option<Account<'info, RoleAssignment>>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Only required when the signer is not the admin
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 1 + 32 + 32 + 8 + 1, // RoleAssignment::LEN
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: receives the role assignment's rent; must be its granter
+    #[account(mut, address = role_assignment.granted_by @ solana_contracts::ErrorCode::Unauthorized)]
+    pub granted_by: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Only required when the signer is not the admin
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+    #[account(
+        mut,
+        close = granted_by,
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + (4 + 32 * 10) + 1 + 4 + 8 + 1, // Multisig::LEN
+        seeds = [b"multisig"],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + 8 + 32 + (1 + (4 + 33 * 16) + (4 + 512)) + (4 + 10) + 4 + 8 + 1, // Proposal::LEN
+        seeds = [b"proposal", (multisig.proposal_count + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ExecuteProposal<'info> {
+    pub executor: Signer<'info>,
+    /// CHECK: receives the proposal's rent; must be its proposer
+    #[account(mut, address = proposal.proposer @ solana_contracts::ErrorCode::Unauthorized)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: data-less PDA the multisig acts as; holds the SOL it spends on rent
+    #[account(
+        mut,
+        seeds = [b"multisig_signer"],
+        bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+    /// Only required for SetFallbackPrice
+    #[account(
+        mut,
+        seeds = [b"price_oracle", price_oracle.mint.as_ref()],
+        bump = price_oracle.bump
+    )]
+    pub price_oracle: Option<Account<'info, PriceOracle>>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CancelProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleParamChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 8 + 9 + 8 + 32 + 8 + 1, // QueuedParamChange::LEN
+        seeds = [b"param_change", (config.param_change_count + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub queued_change: Account<'info, QueuedParamChange>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Emit event
-        emit!(Redeemed {
-            wexel_id: wexel.id,
-            principal_usd: wexel.principal_usd,
-            redeemed_at: clock.unix_timestamp,
-        });
+#[derive(Accounts)]
+#[instruction(change_id: u64)]
+pub struct ExecuteParamChange<'info> {
+    pub executor: Signer<'info>,
+    /// CHECK: receives the queued change's rent; must be its scheduler
+    #[account(mut, address = queued_change.scheduled_by @ solana_contracts::ErrorCode::Unauthorized)]
+    pub scheduled_by: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        close = scheduled_by,
+        seeds = [b"param_change", change_id.to_le_bytes().as_ref()],
+        bump = queued_change.bump
+    )]
+    pub queued_change: Account<'info, QueuedParamChange>,
+}
 
-        Ok(())
-    }
+#[derive(Accounts)]
+#[instruction(change_id: u64)]
+pub struct CancelParamChange<'info> {
+    pub admin: Signer<'info>,
+    /// CHECK: receives the queued change's rent; must be its scheduler
+    #[account(mut, address = queued_change.scheduled_by @ solana_contracts::ErrorCode::Unauthorized)]
+    pub scheduled_by: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        close = scheduled_by,
+        seeds = [b"param_change", change_id.to_le_bytes().as_ref()],
+        bump = queued_change.bump
+    )]
+    pub queued_change: Account<'info, QueuedParamChange>,
 }
 
-// Account contexts
 #[derive(Accounts)]
-#[instruction(pool_id: u64, principal_usd: u64)]
-pub struct Deposit<'info> {
+#[instruction(wexel_id: u64)]
+pub struct ListWexel<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub seller: Signer<'info>,
+    pub wexel: Account<'info, Wexel>,
+    #[account(
+        mut,
+        constraint = seller_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = seller_nft_account.owner == seller.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = seller_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub seller_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    pub payment_mint: Account<'info, Mint>,
     #[account(
         init,
-        payer = user,
-        space = 8 + 8 + 8 + 8 + 2 + 8, // Pool::LEN
-        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        payer = seller,
+        space = 8 + 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + (1 + 8 + 8 + 8 + 8), // Listing::LEN
+        seeds = [b"listing", wexel_id.to_le_bytes().as_ref()],
         bump
     )]
-    pub pool: Account<'info, Pool>,
+    pub listing: Account<'info, Listing>,
     #[account(
         init,
-        payer = user,
-        space = 8 + 8 + 32 + 8 + 2 + 2 + 1 + 8 + 8 + 1 + 1 + 8 + 8, // Wexel::LEN
-        seeds = [b"wexel", user.key().as_ref(), pool_id.to_le_bytes().as_ref()],
-        bump
+        payer = seller,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = listing,
+        associated_token::token_program = token_2022_program
     )]
-    pub wexel: Account<'info, Wexel>,
+    pub escrow_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(wexel_id: u64, amount: u64)]
-pub struct ApplyBoost<'info> {
+#[instruction(wexel_id: u64)]
+pub struct DelistWexel<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub seller: Signer<'info>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        close = seller,
+        seeds = [b"listing", wexel_id.to_le_bytes().as_ref()],
+        bump = listing.bump,
+        has_one = seller @ solana_contracts::ErrorCode::Unauthorized
     )]
-    pub wexel: Account<'info, Wexel>,
+    pub listing: Account<'info, Listing>,
+    #[account(address = listing.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = listing,
+        associated_token::token_program = token_2022_program
+    )]
+    pub escrow_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = seller,
+        associated_token::token_program = token_2022_program
+    )]
+    pub seller_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(wexel_id: u64)]
-pub struct MintWexelFinalize<'info> {
+pub struct BuyWexel<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub buyer: Signer<'info>,
+    /// CHECK: receives the listing and record rent; must be the listing's seller
+    #[account(mut, address = listing.seller @ solana_contracts::ErrorCode::Unauthorized)]
+    pub seller: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        close = seller,
+        seeds = [b"listing", wexel_id.to_le_bytes().as_ref()],
+        bump = listing.bump
     )]
-    pub wexel: Account<'info, Wexel>,
+    pub listing: Box<Account<'info, Listing>>,
+    #[account(
+        mut,
+        close = seller,
+        address = listing.wexel @ solana_contracts::ErrorCode::WexelNotFound
+    )]
+    pub wexel: Box<Account<'info, Wexel>>,
+    #[account(address = listing.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = listing,
+        associated_token::token_program = token_2022_program
+    )]
+    pub escrow_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_2022_program
+    )]
+    pub buyer_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_payment_account.mint == listing.payment_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = buyer_payment_account.owner == buyer.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub buyer_payment_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = seller_payment_account.mint == listing.payment_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = seller_payment_account.owner == listing.seller @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub seller_payment_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = treasury_payment_account.mint == listing.payment_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = treasury_payment_account.owner == config.treasury @ solana_contracts::ErrorCode::InvalidTreasury
+    )]
+    pub treasury_payment_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + 32 + 8 + 1, // UserPositions::LEN
+        seeds = [b"user_positions", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_positions: Box<Account<'info, UserPositions>>,
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + 8 + 32 + 8 + 2 + 2 + 1 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 32 + 8 + 8, // Wexel::LEN
+        seeds = [b"wexel", buyer.key().as_ref(), buyer_positions.wexel_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_wexel: Box<Account<'info, Wexel>>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub payment_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        constraint = bidder_payment_account.mint == payment_mint.key() @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = bidder_payment_account.owner == bidder.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub bidder_payment_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + 32 + 8 + (1 + 8) + ((1 + 8) + 4 + 2 + 8) + 32 + 8 + 8 + 8 + 1 + 1, // Offer::LEN
+        seeds = [b"offer", bidder.key().as_ref(), offer_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    #[account(
+        init,
+        payer = bidder,
+        token::mint = payment_mint,
+        token::authority = offer,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump
+    )]
+    pub offer_escrow: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    /// The bidder, or anyone once the offer has expired
+    pub caller: Signer<'info>,
+    /// CHECK: receives the offer and escrow rent; must be the offer's bidder
+    #[account(mut, address = offer.bidder @ solana_contracts::ErrorCode::Unauthorized)]
+    pub bidder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"offer", offer.bidder.as_ref(), offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+    #[account(
+        mut,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump = offer.escrow_bump
+    )]
+    pub offer_escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = bidder_payment_account.mint == offer.payment_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = bidder_payment_account.owner == offer.bidder @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub bidder_payment_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 #[instruction(wexel_id: u64)]
-pub struct Accrue<'info> {
+pub struct AcceptOffer<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub seller: Signer<'info>,
+    /// CHECK: receives the NFT, offer and escrow rent; must be the offer's bidder
+    #[account(mut, address = offer.bidder @ solana_contracts::ErrorCode::Unauthorized)]
+    pub bidder: UncheckedAccount<'info>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        close = bidder,
+        seeds = [b"offer", offer.bidder.as_ref(), offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump
     )]
-    pub wexel: Account<'info, Wexel>,
+    pub offer: Box<Account<'info, Offer>>,
+    #[account(
+        mut,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump = offer.escrow_bump
+    )]
+    pub offer_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = seller
+    )]
+    pub wexel: Box<Account<'info, Wexel>>,
+    #[account(
+        mut,
+        constraint = seller_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = seller_nft_account.owner == seller.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = seller_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub seller_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = wexel_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_2022_program
+    )]
+    pub bidder_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        mut,
+        constraint = seller_payment_account.mint == offer.payment_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = seller_payment_account.owner == seller.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub seller_payment_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = treasury_payment_account.mint == offer.payment_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = treasury_payment_account.owner == config.treasury @ solana_contracts::ErrorCode::InvalidTreasury
+    )]
+    pub treasury_payment_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + 32 + 8 + 1, // UserPositions::LEN
+        seeds = [b"user_positions", bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_positions: Box<Account<'info, UserPositions>>,
     #[account(
         init,
-        payer = user,
-        space = 8 + 8 + 8, // RewardsVault::LEN
-        seeds = [b"rewards_vault", wexel_id.to_le_bytes().as_ref()],
+        payer = seller,
+        space = 8 + 8 + 32 + 8 + 2 + 2 + 1 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 32 + 8 + 8, // Wexel::LEN
+        seeds = [b"wexel", bidder.key().as_ref(), bidder_positions.wexel_count.to_le_bytes().as_ref()],
         bump
     )]
-    pub rewards_vault: Account<'info, RewardsVault>,
+    pub new_wexel: Box<Account<'info, Wexel>>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(wexel_id: u64)]
-pub struct Claim<'info> {
+pub struct Accrue<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
+    #[account(mut)]
+    pub wexel: Account<'info, Wexel>,
+    #[account(
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() || keeper_role.is_some() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        seeds = [b"rewards_vault", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = rewards_vault.bump
+    )]
+    pub rewards_vault: Account<'info, RewardsVault>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
     )]
+    pub config: Account<'info, GlobalConfig>,
+    /// Lets a keeper accrue wexels it does not hold
+    #[account(
+        seeds = [b"role", [Role::Keeper as u8].as_ref(), user.key().as_ref()],
+        bump = keeper_role.bump
+    )]
+    pub keeper_role: Option<Account<'info, RoleAssignment>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wexel_id: u64)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
     pub wexel: Account<'info, Wexel>,
+    #[account(
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     #[account(
         mut,
-        constraint = rewards_vault.key() == Pubkey::find_program_address(
-            &[b"rewards_vault", wexel_id.to_le_bytes().as_ref()],
-            &crate::ID
-        ).0
+        seeds = [b"rewards_vault", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = rewards_vault.bump
     )]
     pub rewards_vault: Account<'info, RewardsVault>,
+    #[account(
+        mut,
+        seeds = [b"rewards_token_vault", rewards_vault.key().as_ref()],
+        bump = rewards_vault.token_vault_bump
+    )]
+    pub rewards_token_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == rewards_token_vault.mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct ClaimPlatformRewards<'info> {
+    pub caller: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [b"rewards_vault", pool_id.to_le_bytes().as_ref()],
+        bump = rewards_vault.bump
+    )]
+    pub rewards_vault: Account<'info, RewardsVault>,
+    #[account(
+        mut,
+        seeds = [b"rewards_token_vault", rewards_vault.key().as_ref()],
+        bump = rewards_vault.token_vault_bump
+    )]
+    pub rewards_token_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == rewards_token_vault.mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = treasury_token_account.owner == config.treasury @ solana_contracts::ErrorCode::InvalidTreasury
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 #[instruction(wexel_id: u64)]
 pub struct Collateralize<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
+    #[account(mut)]
+    pub wexel: Account<'info, Wexel>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
     )]
-    pub wexel: Account<'info, Wexel>,
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     #[account(
         init,
         payer = user,
-        space = 8 + 8 + 32 + 8 + 2 + 8 + 1, // CollateralPosition::LEN
+        space = 8 + 8 + 32 + 8 + 2 + 8 + 1 + 8 + 8 + 8 + 2 + 8, // CollateralPosition::LEN
         seeds = [b"collateral", wexel_id.to_le_bytes().as_ref()],
         bump
     )]
     pub collateral_position: Account<'info, CollateralPosition>,
+    #[account(
+        mut,
+        seeds = [b"pool", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"pool_vault", pool.key().as_ref()],
+        bump = pool.vault_bump
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.usdt_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: the wexel's NFT mint; its NFT is frozen through Token-2022
+    #[account(address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
 
@@ -570,16 +4341,50 @@ pub struct Collateralize<'info> {
 pub struct RepayLoan<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
+    #[account(mut)]
+    pub wexel: Account<'info, Wexel>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
     )]
-    pub wexel: Account<'info, Wexel>,
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     #[account(
         mut,
+        seeds = [b"collateral", wexel_id.to_le_bytes().as_ref()],
+        bump,
         constraint = collateral_position.wexel_id == wexel_id @ solana_contracts::ErrorCode::WexelNotFound
     )]
     pub collateral_position: Account<'info, CollateralPosition>,
+    #[account(
+        mut,
+        seeds = [b"pool", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"pool_vault", pool.key().as_ref()],
+        bump = pool.vault_bump
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.usdt_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: the wexel's NFT mint; its NFT is thawed through Token-2022
+    #[account(address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
 
@@ -590,8 +4395,134 @@ pub struct Redeem<'info> {
     pub user: Signer<'info>,
     #[account(
         mut,
-        constraint = wexel.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+        close = user
     )]
     pub wexel: Account<'info, Wexel>,
+    #[account(
+        mut,
+        constraint = wexel_nft_account.mint == wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = wexel_nft_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized,
+        constraint = wexel_nft_account.amount == 1 @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub wexel_nft_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"pool", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        seeds = [b"pool_vault", pool.key().as_ref()],
+        bump = pool.vault_bump
+    )]
+    pub pool_vault: Account<'info, TokenAccount>,
+    /// Only required when the wexel has accrued but unclaimed rewards
+    #[account(
+        mut,
+        seeds = [b"rewards_vault", wexel.pool_id.to_le_bytes().as_ref()],
+        bump = rewards_vault.bump
+    )]
+    pub rewards_vault: Option<Account<'info, RewardsVault>>,
+    #[account(mut)]
+    pub rewards_token_vault: Option<Account<'info, TokenAccount>>,
+    /// Only required when the wexel is collateralized
+    #[account(
+        mut,
+        seeds = [b"collateral", wexel_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub collateral_position: Option<Account<'info, CollateralPosition>>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.usdt_mint @ solana_contracts::ErrorCode::InvalidMint,
+        constraint = user_token_account.owner == user.key() @ solana_contracts::ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: the wexel's NFT mint; closed through Token-2022 once the NFT is burned
+    #[account(mut, address = wexel.nft_mint @ solana_contracts::ErrorCode::InvalidMint)]
+    pub wexel_mint: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::solana_contracts::Wexel;
+    use crate::math;
+
+    const CREATED_AT: i64 = 1_700_000_000;
+    const MATURED_AT: i64 = CREATED_AT + 12 * 30 * 86_400;
+
+    fn wexel() -> Wexel {
+        Wexel {
+            id: 0,
+            owner: Default::default(),
+            principal_usd: 1000_000000,
+            apy_bp: 1800,
+            apy_boost_bp: 0,
+            lock_period_months: 12,
+            created_at: CREATED_AT,
+            matured_at: MATURED_AT,
+            is_collateralized: false,
+            is_finalized: false,
+            total_rewards: 0,
+            claimed_rewards: 0,
+            last_accrued_at: CREATED_AT,
+            is_locked: false,
+            pool_id: 0,
+            owner_index: 0,
+            reward_remainder: 0,
+            boost_value_usd: 0,
+            nft_mint: Default::default(),
+            collateralized_at: 0,
+            pending_collateral_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn accrual_stops_at_maturity() {
+        let mut wexel = wexel();
+        let (full_term, _) = math::accrue_reward(1000_000000, 1800, (MATURED_AT - CREATED_AT) as u64, 0).unwrap();
+
+        // Never accrued before; finalizing 90 days late still pays up to maturity only
+        let accrued = wexel.accrue_rewards(MATURED_AT + 90 * 86_400, 4000).unwrap();
+        assert_eq!(accrued, Some((full_term, 0)));
+        assert_eq!(wexel.total_rewards, full_term);
+        assert_eq!(wexel.last_accrued_at, MATURED_AT);
+
+        assert_eq!(wexel.accrue_rewards(MATURED_AT + 180 * 86_400, 4000).unwrap(), None);
+        assert_eq!(wexel.total_rewards, full_term);
+    }
+
+    #[test]
+    fn final_stub_adds_up_to_the_full_term() {
+        let mut wexel = wexel();
+        let (full_term, _) = math::accrue_reward(1000_000000, 1800, (MATURED_AT - CREATED_AT) as u64, 0).unwrap();
+
+        let (first, _) = wexel.accrue_rewards(MATURED_AT - 86_400 / 3, 4000).unwrap().unwrap();
+        let (stub, _) = wexel.accrue_rewards(MATURED_AT + 1, 4000).unwrap().unwrap();
+        assert!(stub > 0);
+        assert_eq!(first + stub, full_term);
+    }
+
+    #[test]
+    fn final_stub_is_split_while_collateralized() {
+        let mut wexel = wexel();
+        wexel.last_accrued_at = MATURED_AT - 86_400;
+        wexel.is_collateralized = true;
+        wexel.collateralized_at = CREATED_AT;
+
+        let (stub, _) = math::accrue_reward(1000_000000, 1800, 86_400, 0).unwrap();
+        let (user, platform) = wexel.accrue_rewards(MATURED_AT + 86_400, 4000).unwrap().unwrap();
+        assert_eq!(user + platform, stub);
+        assert_eq!(platform, math::bps_of(stub, 6000).unwrap());
+        assert_eq!(wexel.total_rewards, user);
+    }
+}
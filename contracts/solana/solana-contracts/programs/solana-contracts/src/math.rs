@@ -0,0 +1,275 @@
+//! Integer reward math shared by the on-chain instructions and off-chain previews.
+//!
+//! Everything here is plain checked `u64`/`u128` arithmetic with floor rounding,
+//! so the same inputs always produce the same outputs on and off chain.
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// USD amounts are tracked in micro-USD.
+pub const USD_DECIMALS: u8 = 6;
+pub const SECONDS_PER_YEAR: u64 = 365 * 86_400;
+
+/// Maximum APY boost, reached when the boost value equals the target.
+pub const BOOST_APY_BP: u16 = 500; // +5%
+/// Boost value needed for the maximum boost, as a share of principal.
+pub const BOOST_TARGET_BP: u16 = 3000; // 30%
+
+/// Denominator of the reward remainder carried between accrual periods.
+pub const REWARD_REMAINDER_DENOMINATOR: u64 = BPS_DENOMINATOR * SECONDS_PER_YEAR;
+
+/// Rewards earned by `principal` at `apy_bp` over `elapsed_seconds`.
+///
+/// `remainder` is the fractional reward left over from the previous period,
+/// expressed as a numerator over [`REWARD_REMAINDER_DENOMINATOR`]. Carrying it
+/// forward means short periods and small principals are never rounded away:
+/// the sum over any split of a period equals the reward for the whole period.
+///
+/// Returns `(reward, new_remainder)`, or `None` on overflow.
+pub fn accrue_reward(
+    principal: u64,
+    apy_bp: u32,
+    elapsed_seconds: u64,
+    remainder: u64,
+) -> Option<(u64, u64)> {
+    let numerator = (principal as u128)
+        .checked_mul(apy_bp as u128)?
+        .checked_mul(elapsed_seconds as u128)?
+        .checked_add(remainder as u128)?;
+    let denominator = REWARD_REMAINDER_DENOMINATOR as u128;
+
+    let reward = u64::try_from(numerator / denominator).ok()?;
+    let new_remainder = (numerator % denominator) as u64;
+    Some((reward, new_remainder))
+}
+
+/// Boost value (in micro-USD) that earns the full `max_boost_bp` for `principal`.
+///
+/// Rounds down. Returns `None` on overflow.
+pub fn boost_target(principal: u64, target_bp: u16) -> Option<u64> {
+    let target = (principal as u128)
+        .checked_mul(target_bp as u128)?
+        .checked_div(BPS_DENOMINATOR as u128)?;
+    u64::try_from(target).ok()
+}
+
+/// APY boost earned by `boost_value` worth of boost tokens on `principal`.
+///
+/// The boost grows linearly with the value up to [`boost_target`] and is capped
+/// at `max_boost_bp`; the result is rounded down. A zero target yields no boost.
+/// Returns `None` on overflow.
+pub fn boost_apy_bp(principal: u64, boost_value: u64, target_bp: u16, max_boost_bp: u16) -> Option<u16> {
+    let target = boost_target(principal, target_bp)?;
+    if target == 0 {
+        return Some(0);
+    }
+
+    let boost = (boost_value.min(target) as u128)
+        .checked_mul(max_boost_bp as u128)?
+        .checked_div(target as u128)?;
+    u16::try_from(boost).ok()
+}
+
+/// Micro-USD value of `amount` base units of a token with `decimals` decimals,
+/// priced at `price * 10^expo` USD per whole token.
+///
+/// Rounds down. Returns `None` on overflow.
+pub fn token_value_usd(amount: u64, price: u64, expo: i32, decimals: u8) -> Option<u64> {
+    // value = amount * price * 10^(expo + USD_DECIMALS - decimals)
+    let scale = expo
+        .checked_add(USD_DECIMALS as i32)?
+        .checked_sub(decimals as i32)?;
+    let value = (amount as u128).checked_mul(price as u128)?;
+    let value = if scale >= 0 {
+        value.checked_mul(10u128.checked_pow(scale as u32)?)?
+    } else {
+        // Divisors beyond u128 range always floor to zero
+        10u128
+            .checked_pow(scale.unsigned_abs())
+            .map_or(0, |divisor| value / divisor)
+    };
+    u64::try_from(value).ok()
+}
+
+/// `bp` basis points of `amount`, rounded down.
+pub fn bps_of(amount: u64, bp: u16) -> Option<u64> {
+    let value = (amount as u128)
+        .checked_mul(bp as u128)?
+        .checked_div(BPS_DENOMINATOR as u128)?;
+    u64::try_from(value).ok()
+}
+
+/// Price of a Dutch auction at `now`, decaying linearly from `start_price` at
+/// `start_time` to `floor_price` at `end_time`.
+///
+/// Holds at `start_price` before the start and at `floor_price` after the end.
+/// The decay is rounded down, so the price never drops early.
+/// Returns `None` on overflow or if `floor_price > start_price`.
+pub fn dutch_auction_price(
+    start_price: u64,
+    floor_price: u64,
+    start_time: i64,
+    end_time: i64,
+    now: i64,
+) -> Option<u64> {
+    let range = start_price.checked_sub(floor_price)?;
+    if now <= start_time {
+        return Some(start_price);
+    }
+    if now >= end_time {
+        return Some(floor_price);
+    }
+
+    let elapsed = now.checked_sub(start_time)? as u128;
+    let duration = end_time.checked_sub(start_time)? as u128;
+    let decay = (range as u128).checked_mul(elapsed)?.checked_div(duration)?;
+    start_price.checked_sub(u64::try_from(decay).ok()?)
+}
+
+/// Split `reward`, earned over `elapsed_seconds`, between the holder and the
+/// platform. The `collateral_seconds` of the period the wexel spent pledged
+/// earn the holder only `user_share_bp`; the rest of that part goes to the
+/// platform.
+///
+/// The platform share is rounded down. Returns `(user, platform)`, or `None`
+/// on overflow, if `collateral_seconds > elapsed_seconds` or if
+/// `user_share_bp` exceeds [`BPS_DENOMINATOR`].
+pub fn split_collateral_reward(
+    reward: u64,
+    elapsed_seconds: u64,
+    collateral_seconds: u64,
+    user_share_bp: u16,
+) -> Option<(u64, u64)> {
+    if collateral_seconds > elapsed_seconds {
+        return None;
+    }
+    if collateral_seconds == 0 {
+        return Some((reward, 0));
+    }
+
+    let collateral_reward = (reward as u128)
+        .checked_mul(collateral_seconds as u128)?
+        .checked_div(elapsed_seconds as u128)?;
+    let platform_share_bp = BPS_DENOMINATOR.checked_sub(user_share_bp as u64)?;
+    let platform = u64::try_from(
+        collateral_reward
+            .checked_mul(platform_share_bp as u128)?
+            .checked_div(BPS_DENOMINATOR as u128)?,
+    )
+    .ok()?;
+    Some((reward.checked_sub(platform)?, platform))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn accrue_reward_carries_the_remainder_across_steps() {
+        let principal = 1000_000000;
+        let whole = accrue_reward(principal, 1800, 30 * DAY, 0).unwrap();
+
+        // Same 30 days accrued in 6-hour steps
+        let mut total = 0;
+        let mut remainder = 0;
+        for _ in 0..120 {
+            let (reward, new_remainder) = accrue_reward(principal, 1800, DAY / 4, remainder).unwrap();
+            total += reward;
+            remainder = new_remainder;
+        }
+
+        assert_eq!((total, remainder), whole);
+    }
+
+    #[test]
+    fn accrue_reward_eventually_pays_small_principals() {
+        let (reward, remainder) = accrue_reward(1, 1800, DAY, 0).unwrap();
+        assert_eq!(reward, 0);
+        assert!(remainder > 0);
+
+        assert_eq!(accrue_reward(1, 1800, 10 * SECONDS_PER_YEAR, 0).unwrap().0, 1);
+    }
+
+    #[test]
+    fn accrue_reward_detects_overflow() {
+        assert_eq!(accrue_reward(u64::MAX, u32::MAX, u64::MAX, 0), None);
+    }
+
+    #[test]
+    fn boost_apy_bp_grows_linearly_up_to_the_cap() {
+        // $100 of a $300 target = 166.66bp, rounded down
+        assert_eq!(boost_apy_bp(1000_000000, 100_000000, BOOST_TARGET_BP, BOOST_APY_BP), Some(166));
+        assert_eq!(boost_apy_bp(1000_000000, 300_000000, BOOST_TARGET_BP, BOOST_APY_BP), Some(500));
+        assert_eq!(boost_apy_bp(1000_000000, 900_000000, BOOST_TARGET_BP, BOOST_APY_BP), Some(500));
+    }
+
+    #[test]
+    fn boost_apy_bp_handles_degenerate_targets() {
+        // The target rounds to zero
+        assert_eq!(boost_apy_bp(3, 1_000000, BOOST_TARGET_BP, BOOST_APY_BP), Some(0));
+        assert_eq!(boost_apy_bp(u64::MAX, u64::MAX, BOOST_TARGET_BP, BOOST_APY_BP), Some(500));
+    }
+
+    #[test]
+    fn token_value_usd_rescales_between_decimals() {
+        // 100 six-decimal tokens at $1.00 (expo -8)
+        assert_eq!(token_value_usd(100_000000, 100_000_000, -8, 6), Some(100_000000));
+        // 1 nine-decimal token at $1.50 (expo -2)
+        assert_eq!(token_value_usd(1_000_000_000, 150, -2, 9), Some(1_500000));
+        // Positive scale
+        assert_eq!(token_value_usd(1, 1, 0, 0), Some(1_000000));
+    }
+
+    #[test]
+    fn token_value_usd_floors_tiny_values_and_detects_overflow() {
+        assert_eq!(token_value_usd(1, 1, -60, 0), Some(0));
+        assert_eq!(token_value_usd(u64::MAX, u64::MAX, 0, 0), None);
+    }
+
+    #[test]
+    fn bps_of_rounds_down() {
+        assert_eq!(bps_of(1000_000000, 6000), Some(600_000000));
+        assert_eq!(bps_of(1, 5000), Some(0));
+        assert_eq!(bps_of(u64::MAX, 10_000), Some(u64::MAX));
+        assert_eq!(bps_of(u64::MAX, 20_000), None);
+    }
+
+    #[test]
+    fn dutch_auction_price_decays_linearly() {
+        assert_eq!(dutch_auction_price(1000, 100, 0, 900, -5), Some(1000));
+        assert_eq!(dutch_auction_price(1000, 100, 0, 900, 0), Some(1000));
+        assert_eq!(dutch_auction_price(1000, 100, 0, 900, 1), Some(999));
+        assert_eq!(dutch_auction_price(1000, 100, 0, 900, 450), Some(550));
+        assert_eq!(dutch_auction_price(1000, 100, 0, 900, 899), Some(101));
+        assert_eq!(dutch_auction_price(1000, 100, 0, 900, 1000), Some(100));
+    }
+
+    #[test]
+    fn dutch_auction_price_never_drops_early() {
+        // A decay of 3.33 is rounded down to 3
+        assert_eq!(dutch_auction_price(10, 0, 0, 3, 1), Some(7));
+        assert_eq!(dutch_auction_price(100, 1000, 0, 900, 450), None);
+    }
+
+    #[test]
+    fn split_collateral_reward_splits_only_the_pledged_part() {
+        let reward = 1000_000000;
+        assert_eq!(split_collateral_reward(reward, DAY, DAY, 4000), Some((400_000000, 600_000000)));
+        // Pledged for 6 of 24 hours
+        assert_eq!(split_collateral_reward(reward, DAY, DAY / 4, 4000), Some((850_000000, 150_000000)));
+        assert_eq!(split_collateral_reward(reward, DAY, 0, 4000), Some((reward, 0)));
+    }
+
+    #[test]
+    fn split_collateral_reward_never_loses_rewards() {
+        let (user, platform) = split_collateral_reward(7, 3, 1, 4000).unwrap();
+        assert_eq!((user, platform), (6, 1));
+        assert_eq!(user + platform, 7);
+    }
+
+    #[test]
+    fn split_collateral_reward_rejects_invalid_input() {
+        assert_eq!(split_collateral_reward(1000, DAY, DAY + 1, 4000), None);
+        assert_eq!(split_collateral_reward(1000, DAY, DAY, 10_001), None);
+    }
+}
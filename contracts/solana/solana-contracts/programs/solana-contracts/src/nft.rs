@@ -0,0 +1,147 @@
+//! Token-2022 NFT that represents a wexel.
+//!
+//! Each wexel has a supply-1, 0-decimals mint whose metadata pointer refers to
+//! the mint itself, with the token metadata stored on the mint. The global
+//! config PDA is the metadata update authority as well as the freeze and close
+//! authority; minting is disabled once the single token exists.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use anchor_spl::token_interface::{
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    token_metadata_initialize, token_metadata_update_field, TokenMetadataInitialize,
+    TokenMetadataUpdateField,
+};
+
+use crate::solana_contracts::Wexel;
+
+pub const WEXEL_NFT_SYMBOL: &str = "WEXEL";
+
+/// Metadata the NFT should carry for the current state of `wexel`.
+pub fn wexel_metadata(wexel: &Wexel, mint: Pubkey, update_authority: Pubkey) -> TokenMetadata {
+    TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(update_authority),
+        mint,
+        name: format!("Wexel #{}", wexel.id),
+        symbol: WEXEL_NFT_SYMBOL.to_string(),
+        uri: String::new(),
+        additional_metadata: vec![
+            ("principal_usd".to_string(), wexel.principal_usd.to_string()),
+            ("apy_bp".to_string(), wexel.apy_bp.to_string()),
+            ("apy_boost_bp".to_string(), wexel.apy_boost_bp.to_string()),
+            ("lock_period_months".to_string(), wexel.lock_period_months.to_string()),
+            ("matured_at".to_string(), wexel.matured_at.to_string()),
+        ],
+    }
+}
+
+/// Accounts needed to write wexel metadata onto its mint.
+pub struct MetadataAccounts<'a, 'info> {
+    pub mint: &'a AccountInfo<'info>,
+    /// Config PDA: mint and metadata update authority
+    pub authority: &'a AccountInfo<'info>,
+    /// Pays for the extra rent as the metadata grows
+    pub payer: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Bring the mint's metadata in line with `wexel`, initializing it on first use.
+///
+/// Only fields that changed are rewritten. Token-2022 reallocates the mint but
+/// does not fund it, so the payer tops up rent for the new size first.
+pub fn sync_wexel_metadata(
+    accounts: MetadataAccounts<'_, '_>,
+    wexel: &Wexel,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let target = wexel_metadata(wexel, accounts.mint.key(), accounts.authority.key());
+
+    let (current, data_len) = {
+        let data = accounts.mint.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+        (mint.get_variable_len_extension::<TokenMetadata>().ok(), data.len())
+    };
+    let current_tlv_len = match &current {
+        Some(metadata) => metadata.tlv_size_of()?,
+        None => 0,
+    };
+    let required_len = data_len
+        .checked_sub(current_tlv_len)
+        .and_then(|len| len.checked_add(target.tlv_size_of().ok()?))
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    let required_lamports = Rent::get()?.minimum_balance(required_len);
+    let top_up = required_lamports.saturating_sub(accounts.mint.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.clone(),
+                system_program::Transfer {
+                    from: accounts.payer.clone(),
+                    to: accounts.mint.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    let current = match current {
+        Some(metadata) => metadata,
+        None => {
+            token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    accounts.token_program.clone(),
+                    TokenMetadataInitialize {
+                        token_program_id: accounts.token_program.clone(),
+                        metadata: accounts.mint.clone(),
+                        update_authority: accounts.authority.clone(),
+                        mint_authority: accounts.authority.clone(),
+                        mint: accounts.mint.clone(),
+                    },
+                    signer_seeds,
+                ),
+                target.name.clone(),
+                target.symbol.clone(),
+                target.uri.clone(),
+            )?;
+            TokenMetadata {
+                name: target.name.clone(),
+                symbol: target.symbol.clone(),
+                uri: target.uri.clone(),
+                ..TokenMetadata::default()
+            }
+        }
+    };
+
+    for (key, value) in &target.additional_metadata {
+        let unchanged = current
+            .additional_metadata
+            .iter()
+            .any(|(k, v)| k == key && v == value);
+        if unchanged {
+            continue;
+        }
+
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                accounts.token_program.clone(),
+                TokenMetadataUpdateField {
+                    token_program_id: accounts.token_program.clone(),
+                    metadata: accounts.mint.clone(),
+                    update_authority: accounts.authority.clone(),
+                },
+                signer_seeds,
+            ),
+            Field::Key(key.clone()),
+            value.clone(),
+        )?;
+    }
+
+    Ok(())
+}
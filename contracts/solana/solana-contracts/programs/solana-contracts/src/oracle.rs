@@ -0,0 +1,258 @@
+//! Price proxy: reads price accounts and aggregates them into one USD price.
+//!
+//! Price accounts use the Pyth v2 (legacy push oracle) layout and must be owned
+//! by the Pyth oracle program; only the fields needed for valuation are read. A [`PriceOracle`] pins up to
+//! [`MAX_PRICE_SOURCES`] of them for a mint, and every pinned source must be
+//! passed so the caller can't pick which ones count. Sources that are stale,
+//! published in the future, not trading or too uncertain are ignored, the
+//! median of the rest is used, and the admin fallback price covers the case
+//! where no source is usable, as long as it was set within `max_age_secs`.
+//!
+//! [`PriceOracle`]: crate::solana_contracts::PriceOracle
+
+use anchor_lang::prelude::*;
+
+use crate::math::{BPS_DENOMINATOR, USD_DECIMALS};
+use crate::solana_contracts::{ErrorCode, PriceOracle};
+
+pub const MAX_PRICE_SOURCES: usize = 4;
+
+/// Pyth v2 oracle program (mainnet-beta)
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+// Byte offsets into a Pyth v2 price account
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 4;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+#[cfg(any(test, feature = "mock-oracle"))]
+const SIZE_OFFSET: usize = 12;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+pub const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+/// Aggregate price published in a price account: `price * 10^expo` USD per token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceData {
+    pub price: u64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl PriceData {
+    /// Price in micro-USD per whole token, rounded down.
+    pub fn price_usd(&self) -> Option<u64> {
+        let scale = self.expo.checked_add(USD_DECIMALS as i32)?;
+        let price = self.price as u128;
+        let price = if scale >= 0 {
+            price.checked_mul(10u128.checked_pow(scale as u32)?)?
+        } else {
+            10u128
+                .checked_pow(scale.unsigned_abs())
+                .map_or(0, |divisor| price / divisor)
+        };
+        u64::try_from(price).ok()
+    }
+
+    /// Whether the confidence interval is within `max_conf_bp` of the price.
+    pub fn is_confident(&self, max_conf_bp: u16) -> bool {
+        (self.conf as u128) * (BPS_DENOMINATOR as u128)
+            <= (self.price as u128) * (max_conf_bp as u128)
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    Some(i32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Option<i64> {
+    Some(i64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// Parse a Pyth v2 price account.
+///
+/// Returns `None` unless the data is a v2 price account with a positive,
+/// currently trading aggregate price.
+pub fn parse_price_account(data: &[u8]) -> Option<PriceData> {
+    if data.len() < PRICE_ACCOUNT_MIN_LEN
+        || read_u32(data, MAGIC_OFFSET)? != PYTH_MAGIC
+        || read_u32(data, VERSION_OFFSET)? != PYTH_VERSION
+        || read_u32(data, ACCOUNT_TYPE_OFFSET)? != PYTH_PRICE_ACCOUNT_TYPE
+        || read_u32(data, AGG_STATUS_OFFSET)? != PYTH_STATUS_TRADING
+    {
+        return None;
+    }
+
+    let price = read_i64(data, AGG_PRICE_OFFSET)?;
+    if price <= 0 {
+        return None;
+    }
+
+    Some(PriceData {
+        price: price as u64,
+        conf: read_u64(data, AGG_CONF_OFFSET)?,
+        expo: read_i32(data, EXPO_OFFSET)?,
+        publish_time: read_i64(data, TIMESTAMP_OFFSET)?,
+    })
+}
+
+/// Serialize `price` into `data` using the Pyth v2 price account layout.
+///
+/// Only used to write mock price accounts for local testing.
+#[cfg(any(test, feature = "mock-oracle"))]
+pub fn write_price_account(data: &mut [u8], price: &PriceData) -> Option<()> {
+    if data.len() < PRICE_ACCOUNT_MIN_LEN {
+        return None;
+    }
+
+    let price_i64 = i64::try_from(price.price).ok()?;
+    let size = u32::try_from(PRICE_ACCOUNT_MIN_LEN).ok()?;
+    data[MAGIC_OFFSET..MAGIC_OFFSET + 4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[VERSION_OFFSET..VERSION_OFFSET + 4].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+    data[ACCOUNT_TYPE_OFFSET..ACCOUNT_TYPE_OFFSET + 4]
+        .copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[SIZE_OFFSET..SIZE_OFFSET + 4].copy_from_slice(&size.to_le_bytes());
+    data[EXPO_OFFSET..EXPO_OFFSET + 4].copy_from_slice(&price.expo.to_le_bytes());
+    data[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&price.publish_time.to_le_bytes());
+    data[AGG_PRICE_OFFSET..AGG_PRICE_OFFSET + 8].copy_from_slice(&price_i64.to_le_bytes());
+    data[AGG_CONF_OFFSET..AGG_CONF_OFFSET + 8].copy_from_slice(&price.conf.to_le_bytes());
+    data[AGG_STATUS_OFFSET..AGG_STATUS_OFFSET + 4]
+        .copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+    Some(())
+}
+
+/// First bytes of a mock price write, which the program's fallback handles in
+/// `mock-oracle` builds.
+#[cfg(feature = "mock-oracle")]
+pub const WRITE_MOCK_PRICE_DISCRIMINATOR: [u8; 8] = *b"mockpxwr";
+
+/// Write a mock price: `args` is the Borsh encoding of `(price: u64, conf: u64,
+/// expo: i32, publish_time: i64)`. The price account must sign and be owned by
+/// this program, so no other program-owned account can be overwritten.
+#[cfg(feature = "mock-oracle")]
+pub fn write_mock_price(program_id: &Pubkey, accounts: &[AccountInfo], args: &[u8]) -> Result<()> {
+    let price_account = accounts.first().ok_or(ErrorCode::InvalidPriceAccount)?;
+    require!(
+        price_account.is_signer && price_account.is_writable && price_account.owner == program_id,
+        ErrorCode::InvalidPriceAccount
+    );
+    let (price, conf, expo, publish_time) = <(u64, u64, i32, i64)>::try_from_slice(args)
+        .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+
+    let mut data = price_account.try_borrow_mut_data()?;
+    write_price_account(&mut data, &PriceData { price, conf, expo, publish_time })
+        .ok_or(ErrorCode::InvalidPriceAccount)?;
+    Ok(())
+}
+
+/// Whether `owner` may own a price account: the Pyth program, plus this
+/// program for mock accounts in `mock-oracle` builds.
+pub fn is_price_account_owner(owner: &Pubkey) -> bool {
+    *owner == PYTH_PROGRAM_ID || (cfg!(feature = "mock-oracle") && *owner == crate::ID)
+}
+
+/// Median of `values`; the mean of the two middle values (rounded down) for an
+/// even count. Returns `None` for an empty slice.
+pub fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Some(values[mid])
+    } else {
+        let sum = values[mid - 1] as u128 + values[mid] as u128;
+        Some((sum / 2) as u64)
+    }
+}
+
+/// Resolve `oracle` to a price in micro-USD per whole token.
+///
+/// `sources` must be exactly the accounts pinned on the oracle, each once, and
+/// owned by the Pyth program.
+/// Unusable sources are skipped; if none is usable the fallback price is
+/// returned when set and no older than `max_age_secs`.
+pub fn resolve_price_usd(oracle: &PriceOracle, sources: &[AccountInfo], now: i64) -> Result<u64> {
+    require!(
+        sources.len() == oracle.sources.len(),
+        ErrorCode::MissingPriceSource
+    );
+
+    let mut prices = Vec::with_capacity(sources.len());
+    for (i, source) in sources.iter().enumerate() {
+        require!(
+            oracle.sources.contains(source.key),
+            ErrorCode::InvalidPriceAccount
+        );
+        require!(
+            !sources[..i].iter().any(|other| other.key == source.key),
+            ErrorCode::InvalidPriceAccount
+        );
+        require!(
+            is_price_account_owner(source.owner),
+            ErrorCode::InvalidPriceAccount
+        );
+
+        let data = source.try_borrow_data()?;
+        let Some(price) = parse_price_account(&data) else {
+            continue;
+        };
+        if price.publish_time > now
+            || now - price.publish_time > oracle.max_age_secs
+            || !price.is_confident(oracle.max_conf_bp)
+        {
+            continue;
+        }
+        if let Some(price_usd) = price.price_usd().filter(|p| *p > 0) {
+            prices.push(price_usd);
+        }
+    }
+
+    match median(&mut prices) {
+        Some(price_usd) => Ok(price_usd),
+        None if oracle.fallback_price_usd > 0
+            && now.saturating_sub(oracle.fallback_updated_at) <= oracle.max_age_secs =>
+        {
+            Ok(oracle.fallback_price_usd)
+        }
+        None => err!(ErrorCode::NoValidPrice),
+    }
+}
+
+/// Validate a price oracle configuration.
+pub fn validate_config(sources: &[Pubkey], max_age_secs: i64, max_conf_bp: u16) -> Result<()> {
+    require!(
+        sources.len() <= MAX_PRICE_SOURCES,
+        ErrorCode::InvalidOracleConfig
+    );
+    for (i, source) in sources.iter().enumerate() {
+        require!(
+            !sources[..i].contains(source),
+            ErrorCode::InvalidOracleConfig
+        );
+    }
+    require!(max_age_secs > 0, ErrorCode::InvalidOracleConfig);
+    require!(
+        max_conf_bp > 0 && max_conf_bp as u64 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidOracleConfig
+    );
+    Ok(())
+}
@@ -1,9 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::Discriminator;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{self, Mint, Token, TokenAccount, Transfer, MintTo},
 };
 
+pub mod multisig;
+
 declare_id!("TKRAxyz111111111111111111111111111111111111");
 
 /// Takara Token Program - Reward token for TAKARA Platform
@@ -27,6 +32,7 @@ pub mod takara_token {
     pub const MINING_POOL_PERCENT: u64 = 60;
     pub const ADMIN_POOL_PERCENT: u64 = 40;
     pub const TAKARA_DECIMALS: u8 = 6;
+    pub const TAKARA_CONFIG_SIZE: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 36 + 14 + 1 + 1 + 32 + 32;
 
     /// Initialize Takara token with mining vault
     /// Creates the token mint and distributes initial supply:
@@ -62,6 +68,8 @@ pub mod takara_token {
         config.token_symbol = token_symbol;
         config.bump = ctx.bumps.takara_config;
         config.mining_vault_bump = ctx.bumps.mining_vault;
+        config.pending_authority = Pubkey::default();
+        config.pending_oracle_authority = Pubkey::default();
 
         // Mint tokens to Mining Vault (60%)
         let cpi_accounts_vault = MintTo {
@@ -95,7 +103,7 @@ pub mod takara_token {
     }
 
     /// Claim Takara rewards from Mining Vault
-    /// Requires oracle signature to verify reward amount
+    /// Requires the oracle authority or an oracle role holder to co-sign the reward amount
     ///
     /// # Arguments
     /// * `reward_amount` - Amount of Takara to claim (calculated by backend)
@@ -111,6 +119,10 @@ pub mod takara_token {
         let clock = Clock::get()?;
 
         // Validate
+        require!(
+            ctx.accounts.oracle.key() == config.oracle_authority || ctx.accounts.oracle_role.is_some(),
+            TakaraError::Unauthorized
+        );
         require!(!config.is_paused, TakaraError::ProgramPaused);
         require!(reward_amount > 0, TakaraError::InvalidAmount);
         require!(
@@ -168,32 +180,61 @@ pub mod takara_token {
     }
 
     /// Update internal Takara price (oracle function)
-    /// Only callable by oracle authority
+    /// Only callable by oracle authority or an oracle role holder
     pub fn update_price(ctx: Context<UpdatePrice>, new_price_usd: u64) -> Result<()> {
         let config = &mut ctx.accounts.takara_config;
 
         require!(
-            ctx.accounts.oracle.key() == config.oracle_authority,
+            ctx.accounts.oracle.key() == config.oracle_authority || ctx.accounts.oracle_role.is_some(),
             TakaraError::Unauthorized
         );
-        require!(new_price_usd > 0, TakaraError::InvalidAmount);
 
-        let old_price = config.internal_price_usd;
-        config.internal_price_usd = new_price_usd;
+        config.update_price(new_price_usd, ctx.accounts.oracle.key())
+    }
 
-        emit!(PriceUpdated {
-            old_price,
-            new_price: new_price_usd,
-            updated_by: ctx.accounts.oracle.key(),
-        });
+    /// Grow a config created before the pending authority fields existed
+    /// Must run once right after upgrading such a deployment; the new fields
+    /// start out empty, i.e. with no proposal pending
+    /// Only callable by authority
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let config_info = ctx.accounts.takara_config.to_account_info();
+
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 + 32 && data[..8] == TakaraConfig::DISCRIMINATOR,
+                TakaraError::InvalidConfig
+            );
+            let authority = Pubkey::try_from(&data[8..40]).map_err(|_| TakaraError::InvalidConfig)?;
+            require_keys_eq!(authority, ctx.accounts.authority.key(), TakaraError::Unauthorized);
+        }
+        require!(
+            config_info.data_len() < TAKARA_CONFIG_SIZE,
+            TakaraError::ConfigUpToDate
+        );
+
+        // Fund the larger account, then grow it; the appended bytes are zeroed
+        let top_up = Rent::get()?
+            .minimum_balance(TAKARA_CONFIG_SIZE)
+            .saturating_sub(config_info.lamports());
+        if top_up > 0 {
+            let cpi_accounts = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: config_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+            anchor_lang::system_program::transfer(cpi_ctx, top_up)?;
+        }
+        config_info.realloc(TAKARA_CONFIG_SIZE, true)?;
 
         Ok(())
     }
 
-    /// Update oracle authority
-    pub fn set_oracle_authority(
-        ctx: Context<SetOracleAuthority>,
-        new_oracle: Pubkey,
+    /// Propose a new authority or oracle authority; the proposed key must accept it
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        role: AuthorityRole,
+        new_authority: Pubkey,
     ) -> Result<()> {
         let config = &mut ctx.accounts.takara_config;
 
@@ -202,23 +243,98 @@ pub mod takara_token {
             TakaraError::Unauthorized
         );
 
-        let old_oracle = config.oracle_authority;
-        config.oracle_authority = new_oracle;
+        config.propose_authority_change(role, new_authority, ctx.accounts.authority.key())
+    }
+
+    /// Accept a pending authority proposal
+    pub fn accept_authority(ctx: Context<AcceptAuthority>, role: AuthorityRole) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        ctx.accounts.takara_config.accept_authority_change(role, new_authority)
+    }
+
+    /// Cancel a pending authority proposal
+    pub fn cancel_authority_proposal(
+        ctx: Context<ProposeAuthority>,
+        role: AuthorityRole,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.takara_config;
+
+        require!(
+            ctx.accounts.authority.key() == config.authority,
+            TakaraError::Unauthorized
+        );
+
+        let pending = match role {
+            AuthorityRole::Admin => &mut config.pending_authority,
+            AuthorityRole::Oracle => &mut config.pending_oracle_authority,
+        };
+        require!(*pending != Pubkey::default(), TakaraError::NoPendingAuthority);
+
+        let cancelled = *pending;
+        *pending = Pubkey::default();
+
+        emit!(AuthorityProposalCancelled {
+            role,
+            cancelled,
+            cancelled_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Give `holder` a role
+    /// Only callable by authority or an admin role holder
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+
+        require!(
+            authority == ctx.accounts.takara_config.authority || ctx.accounts.admin_role.is_some(),
+            TakaraError::Unauthorized
+        );
+
+        let role_assignment = &mut ctx.accounts.role_assignment;
+        role_assignment.role = role;
+        role_assignment.holder = holder;
+        role_assignment.granted_by = authority;
+        role_assignment.granted_at = Clock::get()?.unix_timestamp;
+        role_assignment.bump = ctx.bumps.role_assignment;
+
+        emit!(RoleGranted {
+            role,
+            holder,
+            granted_by: authority,
+        });
+
+        Ok(())
+    }
+
+    /// Take a role away from `holder`; the rent goes back to its granter
+    /// Only callable by authority or an admin role holder
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+
+        require!(
+            authority == ctx.accounts.takara_config.authority || ctx.accounts.admin_role.is_some(),
+            TakaraError::Unauthorized
+        );
 
-        emit!(OracleAuthorityChanged {
-            old_oracle,
-            new_oracle,
+        emit!(RoleRevoked {
+            role,
+            holder,
+            revoked_by: authority,
         });
 
         Ok(())
     }
 
     /// Pause/unpause the program
+    /// Pauser role holders may only pause; unpausing takes the authority
     pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
         let config = &mut ctx.accounts.takara_config;
 
         require!(
-            ctx.accounts.authority.key() == config.authority,
+            ctx.accounts.authority.key() == config.authority
+                || (paused && ctx.accounts.pauser_role.is_some()),
             TakaraError::Unauthorized
         );
 
@@ -232,6 +348,192 @@ pub mod takara_token {
         Ok(())
     }
 
+    /// Create the multisig with its signer set and threshold
+    /// Only callable by authority
+    pub fn create_multisig(ctx: Context<CreateMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.takara_config.authority,
+            TakaraError::Unauthorized
+        );
+        multisig::validate_signers(&signers, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.signers = signers.clone();
+        multisig.threshold = threshold;
+        multisig.signer_set_seqno = 0;
+        multisig.proposal_count = 0;
+        multisig.bump = ctx.bumps.multisig;
+
+        emit!(MultisigCreated {
+            signers,
+            threshold,
+            created_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Open a proposal for `action`; counts as the proposer's approval
+    /// Only callable by multisig signers
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: MultisigAction) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let proposer = ctx.accounts.proposer.key();
+        let index = multisig::signer_index(&multisig.signers, &proposer)?;
+
+        match &action {
+            MultisigAction::ChangeSigners { signers, threshold } => {
+                multisig::validate_signers(signers, *threshold)?;
+            }
+            MultisigAction::Invoke { accounts, data } => {
+                multisig::validate_invoke(accounts, data, &multisig.key())?;
+            }
+            _ => {}
+        }
+
+        multisig.proposal_count = multisig.proposal_count
+            .checked_add(1)
+            .ok_or(TakaraError::MathOverflow)?;
+
+        let mut approved = vec![false; multisig.signers.len()];
+        approved[index] = true;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_id = multisig.proposal_count;
+        proposal.proposer = proposer;
+        proposal.action = action.clone();
+        proposal.approved = approved;
+        proposal.signer_set_seqno = multisig.signer_set_seqno;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.bump = ctx.bumps.proposal;
+
+        emit!(ProposalCreated {
+            proposal_id: proposal.proposal_id,
+            proposer,
+            action,
+        });
+
+        Ok(())
+    }
+
+    /// Approve a proposal
+    /// Only callable by multisig signers
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, proposal_id: u64) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        let signer = ctx.accounts.signer.key();
+
+        require!(proposal.signer_set_seqno == multisig.signer_set_seqno, TakaraError::StaleProposal);
+        let index = multisig::signer_index(&multisig.signers, &signer)?;
+        require!(!proposal.approved[index], TakaraError::AlreadyApproved);
+        proposal.approved[index] = true;
+
+        emit!(ProposalApproved {
+            proposal_id,
+            signer,
+            approvals: multisig::approval_count(&proposal.approved) as u8,
+        });
+
+        Ok(())
+    }
+
+    /// Apply an approved proposal's action and close it
+    /// Callable by anyone once the threshold is reached
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+        proposal_id: u64,
+    ) -> Result<()> {
+        let signer_key = ctx.accounts.multisig_signer.key();
+        let multisig = &mut ctx.accounts.multisig;
+        let proposal = &ctx.accounts.proposal;
+        let config = &mut ctx.accounts.takara_config;
+
+        require!(proposal.signer_set_seqno == multisig.signer_set_seqno, TakaraError::StaleProposal);
+        require!(
+            multisig::approval_count(&proposal.approved) >= multisig.threshold as usize,
+            TakaraError::ThresholdNotReached
+        );
+
+        // The multisig acts with whatever roles have been handed to its signer PDA
+        match proposal.action.clone() {
+            MultisigAction::SetPaused(paused) => {
+                require!(config.authority == signer_key, TakaraError::Unauthorized);
+                config.is_paused = paused;
+
+                emit!(PauseStatusChanged {
+                    paused,
+                    changed_by: signer_key,
+                });
+            }
+            MultisigAction::UpdatePrice(new_price_usd) => {
+                require!(config.oracle_authority == signer_key, TakaraError::Unauthorized);
+                config.update_price(new_price_usd, signer_key)?;
+            }
+            MultisigAction::ProposeAuthority { role, new_authority } => {
+                require!(config.authority == signer_key, TakaraError::Unauthorized);
+                config.propose_authority_change(role, new_authority, signer_key)?;
+            }
+            MultisigAction::AcceptAuthority(role) => {
+                config.accept_authority_change(role, signer_key)?;
+            }
+            MultisigAction::ChangeSigners { signers, threshold } => {
+                multisig::validate_signers(&signers, threshold)?;
+                multisig.signers = signers.clone();
+                multisig.threshold = threshold;
+                multisig.signer_set_seqno = multisig.signer_set_seqno
+                    .checked_add(1)
+                    .ok_or(TakaraError::MathOverflow)?;
+
+                emit!(MultisigSignersChanged {
+                    signers,
+                    threshold,
+                    signer_set_seqno: multisig.signer_set_seqno,
+                });
+            }
+            MultisigAction::Invoke { accounts, data } => {
+                let ix = Instruction {
+                    program_id: crate::ID,
+                    accounts: accounts
+                        .iter()
+                        .map(|account| AccountMeta {
+                            pubkey: account.pubkey,
+                            is_signer: account.pubkey == signer_key,
+                            is_writable: account.is_writable,
+                        })
+                        .collect(),
+                    data,
+                };
+                let mut account_infos = ctx.remaining_accounts.to_vec();
+                account_infos.push(ctx.accounts.multisig_signer.to_account_info());
+                invoke_signed(
+                    &ix,
+                    &account_infos,
+                    &[&[b"multisig_signer", &[ctx.bumps.multisig_signer]]],
+                )?;
+
+                // The invoked instruction may have written the config held here; reload so exit keeps its writes
+                config.reload()?;
+            }
+        }
+
+        emit!(ProposalExecuted {
+            proposal_id,
+            executed_by: ctx.accounts.executor.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a proposal and refund its rent
+    /// Only callable by the proposer
+    pub fn cancel_proposal(ctx: Context<CancelProposal>, proposal_id: u64) -> Result<()> {
+        emit!(ProposalCancelled {
+            proposal_id,
+            cancelled_by: ctx.accounts.proposer.key(),
+        });
+
+        Ok(())
+    }
+
     /// Get mining pool statistics
     pub fn get_mining_stats(ctx: Context<GetMiningStats>) -> Result<MiningStats> {
         let config = &ctx.accounts.takara_config;
@@ -269,6 +571,139 @@ pub struct TakaraConfig {
     pub token_symbol: String,        // Max 10 chars
     pub bump: u8,
     pub mining_vault_bump: u8,
+    pub pending_authority: Pubkey,        // Proposed authority, default when none
+    pub pending_oracle_authority: Pubkey, // Proposed oracle authority, default when none
+}
+
+impl TakaraConfig {
+    fn update_price(&mut self, new_price_usd: u64, updated_by: Pubkey) -> Result<()> {
+        require!(new_price_usd > 0, TakaraError::InvalidAmount);
+
+        let old_price = self.internal_price_usd;
+        self.internal_price_usd = new_price_usd;
+
+        emit!(PriceUpdated {
+            old_price,
+            new_price: new_price_usd,
+            updated_by,
+        });
+
+        Ok(())
+    }
+
+    fn propose_authority_change(&mut self, role: AuthorityRole, new_authority: Pubkey, proposed_by: Pubkey) -> Result<()> {
+        let current = match role {
+            AuthorityRole::Admin => self.authority,
+            AuthorityRole::Oracle => self.oracle_authority,
+        };
+        require!(
+            new_authority != Pubkey::default() && new_authority != current,
+            TakaraError::InvalidAuthority
+        );
+
+        match role {
+            AuthorityRole::Admin => self.pending_authority = new_authority,
+            AuthorityRole::Oracle => self.pending_oracle_authority = new_authority,
+        }
+
+        emit!(AuthorityProposed {
+            role,
+            current,
+            proposed: new_authority,
+            proposed_by,
+        });
+
+        Ok(())
+    }
+
+    fn accept_authority_change(&mut self, role: AuthorityRole, new_authority: Pubkey) -> Result<()> {
+        let (current, pending) = match role {
+            AuthorityRole::Admin => (&mut self.authority, &mut self.pending_authority),
+            AuthorityRole::Oracle => (&mut self.oracle_authority, &mut self.pending_oracle_authority),
+        };
+        require!(*pending != Pubkey::default(), TakaraError::NoPendingAuthority);
+        require!(*pending == new_authority, TakaraError::Unauthorized);
+
+        let old_authority = *current;
+        *current = new_authority;
+        *pending = Pubkey::default();
+
+        emit!(AuthorityAccepted {
+            role,
+            old_authority,
+            new_authority,
+        });
+
+        Ok(())
+    }
+}
+
+/// M-of-N signer set. Proposals execute once `threshold` signers approve;
+/// the multisig acts with the roles handed to its signer PDA through the authority handover.
+#[account]
+pub struct Multisig {
+    pub signers: Vec<Pubkey>,        // At most multisig::MAX_MULTISIG_SIGNERS
+    pub threshold: u8,
+    pub signer_set_seqno: u32,       // Bumped on every signer change, voiding open proposals
+    pub proposal_count: u64,         // Last issued proposal id
+    pub bump: u8,
+}
+
+/// Privileged actions a multisig proposal can carry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum MultisigAction {
+    SetPaused(bool),
+    UpdatePrice(u64),
+    ProposeAuthority { role: AuthorityRole, new_authority: Pubkey },
+    AcceptAuthority(AuthorityRole),
+    ChangeSigners { signers: Vec<Pubkey>, threshold: u8 },
+    // Any instruction of this program, signed by the multisig signer PDA
+    Invoke { accounts: Vec<InvokeAccount>, data: Vec<u8> },
+}
+
+/// Account of an invoked instruction; only the multisig signer PDA signs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct InvokeAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+/// One action awaiting approvals; closed when executed or cancelled
+#[account]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,            // Paid the rent, refunded on close
+    pub action: MultisigAction,
+    pub approved: Vec<bool>,         // One flag per signer, in signer order
+    pub signer_set_seqno: u32,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// One per (role, holder), seeded by [b"role", role, holder]
+#[account]
+pub struct RoleAssignment {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub granted_by: Pubkey,          // Paid the rent, refunded on revoke
+    pub granted_at: i64,
+    pub bump: u8,
+}
+
+/// Same role set as solana_contracts; Treasurer and Keeper gate nothing here yet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Admin,     // grant_role, revoke_role
+    Pauser,    // set_paused, pausing only
+    Oracle,    // update_price, claim_takara
+    Treasurer,
+    Keeper,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityRole {
+    Admin,
+    Oracle,
 }
 
 #[account]
@@ -304,7 +739,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 36 + 14 + 1 + 1, // TakaraConfig size
+        space = takara_token::TAKARA_CONFIG_SIZE,
         seeds = [b"takara_config"],
         bump
     )]
@@ -347,10 +782,16 @@ pub struct ClaimTakara<'info> {
         mut,
         seeds = [b"takara_config"],
         bump = takara_config.bump,
-        constraint = oracle.key() == takara_config.oracle_authority @ TakaraError::Unauthorized
     )]
     pub takara_config: Account<'info, TakaraConfig>,
 
+    /// Only required when the co-signer is not the oracle authority
+    #[account(
+        seeds = [b"role", [Role::Oracle as u8].as_ref(), oracle.key().as_ref()],
+        bump = oracle_role.bump,
+    )]
+    pub oracle_role: Option<Account<'info, RoleAssignment>>,
+
     #[account(
         mut,
         seeds = [b"mining_vault", takara_config.token_mint.as_ref()],
@@ -395,12 +836,48 @@ pub struct UpdatePrice<'info> {
         bump = takara_config.bump,
     )]
     pub takara_config: Account<'info, TakaraConfig>,
+
+    /// Only required when the signer is not the oracle authority
+    #[account(
+        seeds = [b"role", [Role::Oracle as u8].as_ref(), oracle.key().as_ref()],
+        bump = oracle_role.bump,
+    )]
+    pub oracle_role: Option<Account<'info, RoleAssignment>>,
 }
 
 #[derive(Accounts)]
-pub struct SetOracleAuthority<'info> {
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// CHECK: may still have the old layout, so it is checked in migrate_config
+    #[account(
+        mut,
+        seeds = [b"takara_config"],
+        bump,
+        owner = crate::ID @ TakaraError::InvalidConfig,
+    )]
+    pub takara_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"takara_config"],
+        bump = takara_config.bump,
+    )]
+    pub takara_config: Account<'info, TakaraConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
     #[account(
         mut,
         seeds = [b"takara_config"],
@@ -419,6 +896,75 @@ pub struct SetPaused<'info> {
         bump = takara_config.bump,
     )]
     pub takara_config: Account<'info, TakaraConfig>,
+
+    /// Only required when the signer is not the authority
+    #[account(
+        seeds = [b"role", [Role::Pauser as u8].as_ref(), authority.key().as_ref()],
+        bump = pauser_role.bump,
+    )]
+    pub pauser_role: Option<Account<'info, RoleAssignment>>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"takara_config"],
+        bump = takara_config.bump,
+    )]
+    pub takara_config: Account<'info, TakaraConfig>,
+
+    /// Only required when the signer is not the authority
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump,
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 1 + 32 + 32 + 8 + 1, // RoleAssignment size
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: receives the role assignment's rent; must be its granter
+    #[account(mut, address = role_assignment.granted_by @ TakaraError::Unauthorized)]
+    pub granted_by: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"takara_config"],
+        bump = takara_config.bump,
+    )]
+    pub takara_config: Account<'info, TakaraConfig>,
+
+    /// Only required when the signer is not the authority
+    #[account(
+        seeds = [b"role", [Role::Admin as u8].as_ref(), authority.key().as_ref()],
+        bump = admin_role.bump,
+    )]
+    pub admin_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        close = granted_by,
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
 }
 
 #[derive(Accounts)]
@@ -430,6 +976,128 @@ pub struct GetMiningStats<'info> {
     pub takara_config: Account<'info, TakaraConfig>,
 }
 
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"takara_config"],
+        bump = takara_config.bump,
+    )]
+    pub takara_config: Account<'info, TakaraConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + (4 + 32 * 10) + 1 + 4 + 8 + 1, // Multisig size
+        seeds = [b"multisig"],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + 8 + 32 + (1 + (4 + 33 * 16) + (4 + 512)) + (4 + 10) + 4 + 8 + 1, // Proposal size
+        seeds = [b"proposal", (multisig.proposal_count + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"multisig"],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ExecuteProposal<'info> {
+    pub executor: Signer<'info>,
+
+    /// CHECK: receives the proposal's rent; must be its proposer
+    #[account(mut, address = proposal.proposer @ TakaraError::Unauthorized)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"takara_config"],
+        bump = takara_config.bump,
+    )]
+    pub takara_config: Account<'info, TakaraConfig>,
+
+    /// CHECK: data-less PDA the multisig acts as; holds the SOL it spends on rent
+    #[account(
+        mut,
+        seeds = [b"multisig_signer"],
+        bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CancelProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer @ TakaraError::Unauthorized,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
 // ============================================
 // Return Types
 // ============================================
@@ -475,9 +1143,79 @@ pub struct PriceUpdated {
 }
 
 #[event]
-pub struct OracleAuthorityChanged {
-    pub old_oracle: Pubkey,
-    pub new_oracle: Pubkey,
+pub struct MultisigCreated {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub created_by: Pubkey,
+}
+
+#[event]
+pub struct MultisigSignersChanged {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub signer_set_seqno: u32,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: MultisigAction,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub proposal_id: u64,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal_id: u64,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub proposal_id: u64,
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub revoked_by: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub role: AuthorityRole,
+    pub current: Pubkey,
+    pub proposed: Pubkey,
+    pub proposed_by: Pubkey,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+    pub role: AuthorityRole,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposalCancelled {
+    pub role: AuthorityRole,
+    pub cancelled: Pubkey,
+    pub cancelled_by: Pubkey,
 }
 
 #[event]
@@ -508,4 +1246,24 @@ pub enum TakaraError {
     InvalidMint,
     #[msg("Invalid signature")]
     InvalidSignature,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("No pending authority proposal")]
+    NoPendingAuthority,
+    #[msg("Invalid multisig signers or threshold")]
+    InvalidMultisig,
+    #[msg("Signer is not part of the multisig")]
+    NotMultisigSigner,
+    #[msg("Proposal already approved by this signer")]
+    AlreadyApproved,
+    #[msg("Proposal has not reached the approval threshold")]
+    ThresholdNotReached,
+    #[msg("Proposal predates the current signer set")]
+    StaleProposal,
+    #[msg("Invalid Takara config account")]
+    InvalidConfig,
+    #[msg("Config already has the current layout")]
+    ConfigUpToDate,
+    #[msg("Invalid instruction for the multisig to invoke")]
+    InvalidInvoke,
 }
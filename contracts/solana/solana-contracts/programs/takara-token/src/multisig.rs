@@ -0,0 +1,75 @@
+//! M-of-N approval for privileged instructions.
+//!
+//! A [`Multisig`] holds a signer set and a threshold. Any signer may open a
+//! [`Proposal`] describing one action; once `threshold` signers have approved
+//! it, anyone can execute it and the program applies the action itself. The
+//! multisig acts as the data-less `multisig_signer` PDA: the authority or
+//! oracle authority is handed to it with `propose_authority` and an executed
+//! `AcceptAuthority` proposal.
+//!
+//! Common actions have their own [`MultisigAction`] variant. Every other
+//! instruction of this program, such as `grant_role` or `revoke_role`, is
+//! reached through `Invoke`, which calls it with the signer PDA signing.
+//! Instructions that create accounts with the authority as payer draw the rent
+//! from the signer PDA, so keep it funded.
+//!
+//! Changing the signer set bumps `signer_set_seqno`, which invalidates every
+//! open proposal and its approvals.
+//!
+//! [`Multisig`]: crate::Multisig
+//! [`Proposal`]: crate::Proposal
+//! [`MultisigAction`]: crate::MultisigAction
+
+use anchor_lang::prelude::*;
+
+use crate::{InvokeAccount, TakaraError};
+
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+pub const MAX_INVOKE_ACCOUNTS: usize = 16;
+pub const MAX_INVOKE_DATA_LEN: usize = 512;
+
+/// Check a signer set: 1..=MAX_MULTISIG_SIGNERS distinct keys and 1 <= threshold <= signers.
+pub fn validate_signers(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+        TakaraError::InvalidMultisig
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        TakaraError::InvalidMultisig
+    );
+    for (i, signer) in signers.iter().enumerate() {
+        require!(*signer != Pubkey::default(), TakaraError::InvalidMultisig);
+        require!(!signers[..i].contains(signer), TakaraError::InvalidMultisig);
+    }
+    Ok(())
+}
+
+/// Check an `Invoke` action: it fits the proposal account, carries an
+/// instruction discriminator and never touches the multisig, so a proposal
+/// can't approve or execute other proposals.
+pub fn validate_invoke(accounts: &[InvokeAccount], data: &[u8], multisig: &Pubkey) -> Result<()> {
+    require!(accounts.len() <= MAX_INVOKE_ACCOUNTS, TakaraError::InvalidInvoke);
+    require!(
+        data.len() >= 8 && data.len() <= MAX_INVOKE_DATA_LEN,
+        TakaraError::InvalidInvoke
+    );
+    require!(
+        accounts.iter().all(|account| account.pubkey != *multisig),
+        TakaraError::InvalidInvoke
+    );
+    Ok(())
+}
+
+/// Position of `key` in the signer set.
+pub fn signer_index(signers: &[Pubkey], key: &Pubkey) -> Result<usize> {
+    signers
+        .iter()
+        .position(|signer| signer == key)
+        .ok_or_else(|| error!(TakaraError::NotMultisigSigner))
+}
+
+/// Number of approvals recorded on a proposal.
+pub fn approval_count(approved: &[bool]) -> usize {
+    approved.iter().filter(|&&approved| approved).count()
+}
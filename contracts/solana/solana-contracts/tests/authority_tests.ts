@@ -0,0 +1,125 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import { ensureConfig } from "./helpers";
+
+describe("Authority Handover Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const admin = provider.wallet;
+
+  const ADMIN = { admin: {} };
+  const ORACLE = { oracle: {} };
+
+  let config: anchor.web3.PublicKey;
+
+  async function propose(role: object, newAuthority: anchor.web3.PublicKey) {
+    await program.methods
+      .proposeAuthority(role as any, newAuthority)
+      .accounts({ admin: admin.publicKey, config })
+      .rpc();
+  }
+
+  async function accept(role: object, signer?: anchor.web3.Keypair) {
+    const builder = program.methods
+      .acceptAuthority(role as any)
+      .accounts({ newAuthority: signer ? signer.publicKey : admin.publicKey, config });
+    await (signer ? builder.signers([signer]) : builder).rpc();
+  }
+
+  async function cancel(role: object) {
+    await program.methods
+      .cancelAuthorityProposal(role as any)
+      .accounts({ admin: admin.publicKey, config })
+      .rpc();
+  }
+
+  before(async () => {
+    config = await ensureConfig(program, provider);
+  });
+
+  it("should hand the oracle role over only once the new key accepts", async () => {
+    const newOracle = anchor.web3.Keypair.generate();
+
+    await propose(ORACLE, newOracle.publicKey);
+
+    let configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.oracleAuthority.toString()).to.equal(admin.publicKey.toString());
+    expect(configAccount.pendingOracleAuthority.toString()).to.equal(newOracle.publicKey.toString());
+
+    await accept(ORACLE, newOracle);
+
+    configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.oracleAuthority.toString()).to.equal(newOracle.publicKey.toString());
+    expect(configAccount.pendingOracleAuthority.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+
+    // Hand it back so other suites can keep setting fallback prices
+    await propose(ORACLE, admin.publicKey);
+    await accept(ORACLE);
+  });
+
+  it("should only let the proposed key accept", async () => {
+    const proposed = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+
+    await propose(ADMIN, proposed.publicKey);
+
+    try {
+      await accept(ADMIN, stranger);
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.admin.toString()).to.equal(admin.publicKey.toString());
+
+    await cancel(ADMIN);
+  });
+
+  it("should drop a cancelled proposal", async () => {
+    const proposed = anchor.web3.Keypair.generate();
+
+    await propose(ADMIN, proposed.publicKey);
+    await cancel(ADMIN);
+
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.pendingAdmin.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+
+    try {
+      await accept(ADMIN, proposed);
+      expect.fail("Should have failed with NoPendingAuthority error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("NoPendingAuthority");
+    }
+  });
+
+  it("should refuse to propose the current or default key", async () => {
+    for (const key of [admin.publicKey, anchor.web3.PublicKey.default]) {
+      try {
+        await propose(ADMIN, key);
+        expect.fail("Should have failed with InvalidAuthority error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidAuthority");
+      }
+    }
+  });
+
+  it("should only let the admin propose", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+
+    try {
+      await program.methods
+        .proposeAuthority(ADMIN as any, stranger.publicKey)
+        .accounts({ admin: stranger.publicKey, config })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+  });
+});
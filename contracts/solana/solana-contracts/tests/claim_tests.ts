@@ -0,0 +1,95 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import {
+  configPda,
+  createPool,
+  deposit,
+  fundRewardsVault,
+  initializeRewardsVault,
+  rewardsVaultPdas,
+  setUsdtAccounts,
+  startBank,
+  warpForward,
+  wexelNftAccount,
+} from "./helpers";
+
+// Runs on bankrun: accrue needs a day to pass since the last accrual
+describe("Claim Payout Tests", () => {
+  const SECONDS_PER_DAY = 86_400;
+  const SECONDS_PER_YEAR = 365 * SECONDS_PER_DAY;
+  const poolId = new anchor.BN(1);
+
+  let context: ProgramTestContext;
+  let program: Program<SolanaContracts>;
+  let provider: anchor.AnchorProvider;
+  let userTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    ({ context, program, provider } = await startBank());
+    const usdt = setUsdtAccounts(context, provider.wallet.publicKey, 10_000_000000);
+    userTokenAccount = usdt.tokenAccount;
+
+    await createPool(program, provider, poolId, usdt.usdtMint);
+    await initializeRewardsVault(program, provider, poolId, usdt.usdtMint);
+    await fundRewardsVault(program, provider, poolId, userTokenAccount, 1_000_000000);
+  });
+
+  it("should pay accrued rewards out of the rewards token vault", async () => {
+    const user = provider.wallet.publicKey;
+    const principalUsd = new anchor.BN(1000_000000);
+    const { wexel, wexelId } = await deposit(program, poolId, principalUsd, user, userTokenAccount);
+    const { rewardsVault, rewardsTokenVault } = rewardsVaultPdas(program.programId, poolId);
+
+    const elapsed = 30 * SECONDS_PER_DAY;
+    await warpForward(context, elapsed);
+
+    await program.methods
+      .accrue(wexelId)
+      .accounts({
+        user,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user),
+        rewardsVault,
+        config: configPda(program.programId),
+        keeperRole: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const accrued = await program.account.wexel.fetch(wexel);
+    const expectedReward = principalUsd.muln(accrued.apyBp).muln(elapsed).divn(10_000).divn(SECONDS_PER_YEAR);
+    expect(accrued.totalRewards.toString()).to.equal(expectedReward.toString());
+
+    const vaultBefore = (await getAccount(provider.connection, rewardsTokenVault)).amount;
+    const userBefore = (await getAccount(provider.connection, userTokenAccount)).amount;
+
+    await program.methods
+      .claim(wexelId)
+      .accounts({
+        user,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user),
+        rewardsVault,
+        rewardsTokenVault,
+        userTokenAccount,
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const vaultAfter = (await getAccount(provider.connection, rewardsTokenVault)).amount;
+    const userAfter = (await getAccount(provider.connection, userTokenAccount)).amount;
+    expect((vaultBefore - vaultAfter).toString()).to.equal(expectedReward.toString());
+    expect((userAfter - userBefore).toString()).to.equal(expectedReward.toString());
+
+    const claimed = await program.account.wexel.fetch(wexel);
+    expect(claimed.claimedRewards.toString()).to.equal(expectedReward.toString());
+    const vaultAccount = await program.account.rewardsVault.fetch(rewardsVault);
+    expect(vaultAccount.distributedRewards.toString()).to.equal(expectedReward.toString());
+  });
+});
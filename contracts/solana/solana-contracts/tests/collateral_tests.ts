@@ -212,9 +212,8 @@ describe("Collateral Tests (collateralize, repay_loan, redeem)", () => {
       const wexel = await program.account.wexel.fetch(wexelPda);
       expect(wexel.isCollateralized).to.be.false;
 
-      // Verify collateral position is marked as repaid
-      const collateralAfter = await program.account.collateralPosition.fetch(collateralPda);
-      expect(collateralAfter.isRepaid).to.be.true;
+      // Verify collateral position is closed once the debt is repaid
+      expect(await provider.connection.getAccountInfo(collateralPda)).to.be.null;
     });
 
     it("should not allow repaying loan that doesn't exist", async () => {
@@ -246,7 +245,7 @@ describe("Collateral Tests (collateralize, repay_loan, redeem)", () => {
       }
     });
 
-    it("should not allow repaying with insufficient amount", async () => {
+    it("should keep the loan open after a partial repayment", async () => {
       const poolId = Math.floor(Math.random() * 1000000);
       const principalUsd = 1000_000000;
 
@@ -268,24 +267,25 @@ describe("Collateral Tests (collateralize, repay_loan, redeem)", () => {
         })
         .rpc();
 
-      // Try to repay with insufficient amount
-      const insufficientAmount = 100_000000; // Only $100, need $600
+      // Repay part of the loan
+      const partialAmount = 100_000000; // Only $100 of $600
 
-      try {
-        await program.methods
-          .repayLoan(new anchor.BN(poolId), new anchor.BN(insufficientAmount))
-          .accounts({
-            user: user.publicKey,
-            wexel: wexelPda,
-            collateralPosition: collateralPda,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .rpc();
+      await program.methods
+        .repayLoan(new anchor.BN(poolId), new anchor.BN(partialAmount))
+        .accounts({
+          user: user.publicKey,
+          wexel: wexelPda,
+          collateralPosition: collateralPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
 
-        expect.fail("Should have thrown an error");
-      } catch (err) {
-        expect(err.toString()).to.include("InvalidRepaymentAmount");
-      }
+      const collateral = await program.account.collateralPosition.fetch(collateralPda);
+      expect(collateral.principalUsd.toNumber()).to.equal(500_000000);
+      expect(collateral.isRepaid).to.be.false;
+
+      const wexel = await program.account.wexel.fetch(wexelPda);
+      expect(wexel.isCollateralized).to.be.true;
     });
 
     it("should allow repaying with exact or higher amount", async () => {
@@ -377,7 +377,8 @@ describe("Collateral Tests (collateralize, repay_loan, redeem)", () => {
 
         expect.fail("Should have thrown an error");
       } catch (err) {
-        expect(err.toString()).to.include("InvalidRepaymentAmount");
+        // The position was closed by the first repayment
+        expect(err.toString()).to.include("AccountNotInitialized");
       }
     });
   });
@@ -0,0 +1,135 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  configPda,
+  createPool,
+  createUsdtMint,
+  deposit,
+  ensureConfig,
+  fundTokenAccount,
+  initializeRewardsVault,
+  poolPdas,
+  wexelNftAccount,
+} from "./helpers";
+
+describe("Global Config Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const admin = provider.wallet;
+
+  const unpaused = { deposit: false, boost: false, collateralize: false, claim: false, marketplace: false };
+
+  let config: anchor.web3.PublicKey;
+  let usdtMint: anchor.web3.PublicKey;
+  let userTokenAccount: anchor.web3.PublicKey;
+  let poolId: anchor.BN;
+
+  async function setPaused(paused: typeof unpaused, signer?: anchor.web3.Keypair) {
+    const builder = program.methods
+      .setPaused(paused)
+      .accounts({ authority: signer ? signer.publicKey : admin.publicKey, config });
+    await (signer ? builder.signers([signer]) : builder).rpc();
+  }
+
+  before(async () => {
+    config = await ensureConfig(program, provider);
+    usdtMint = await createUsdtMint(provider);
+    userTokenAccount = await fundTokenAccount(provider, usdtMint, admin.publicKey, 10_000_000000);
+    poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+  });
+
+  after(async () => {
+    await setPaused(unpaused);
+  });
+
+  it("should block deposits while deposits are paused", async () => {
+    await setPaused({ ...unpaused, deposit: true });
+
+    try {
+      await deposit(program, poolId, new anchor.BN(1000_000000), admin.publicKey, userTokenAccount);
+      expect.fail("Should have failed with OperationPaused error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("OperationPaused");
+    }
+
+    // Other operations keep their own switch
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.paused.deposit).to.equal(true);
+    expect(configAccount.paused.marketplace).to.equal(false);
+
+    await setPaused(unpaused);
+    await deposit(program, poolId, new anchor.BN(1000_000000), admin.publicKey, userTokenAccount);
+  });
+
+  it("should block finalizing while claims are paused", async () => {
+    const { rewardsVault } = await initializeRewardsVault(program, provider, poolId, usdtMint);
+    const { wexel, wexelId } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      admin.publicKey,
+      userTokenAccount
+    );
+    await setPaused({ ...unpaused, claim: true });
+
+    // Checked before maturity, so a fresh wexel shows the pause
+    try {
+      await program.methods
+        .mintWexelFinalize(wexelId)
+        .accounts({
+          user: admin.publicKey,
+          wexel,
+          wexelNftAccount: wexelNftAccount(program.programId, wexel, admin.publicKey),
+          rewardsVault,
+          config: configPda(program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("Should have failed with OperationPaused error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("OperationPaused");
+    }
+
+    await setPaused(unpaused);
+  });
+
+  it("should only let the admin pause", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+
+    try {
+      await setPaused({ ...unpaused, deposit: true }, stranger);
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+  });
+
+  it("should refuse pools in a mint that is not accepted", async () => {
+    const { acceptedMints } = await program.account.globalConfig.fetch(config);
+    const otherMint = await createUsdtMint(provider);
+    const otherPoolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    const { pool, poolVault } = poolPdas(program.programId, otherPoolId);
+
+    try {
+      await program.methods
+        .createPool(otherPoolId, [{ months: 12, apyBp: 1800 }], new anchor.BN(1_000000), new anchor.BN(1_000_000000), 0)
+        .accounts({
+          admin: admin.publicKey,
+          config,
+          usdtMint: otherMint,
+          pool,
+          poolVault,
+        })
+        .rpc();
+      expect.fail("Should have failed with MintNotAccepted error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("MintNotAccepted");
+    }
+    expect(acceptedMints.some((mint) => mint.equals(otherMint))).to.equal(false);
+  });
+});
@@ -109,6 +109,41 @@ describe("Solana Contract Math Operations", () => {
     });
   });
 
+  describe("Loan Settlement at Maturity", () => {
+    // Mirrors redeem: the debt is netted out of principal first, then rewards
+    function settle(principal: number, rewards: number, debt: number) {
+      const fromPrincipal = Math.min(debt, principal);
+      const fromRewards = Math.min(debt - fromPrincipal, rewards);
+      return {
+        fromPrincipal,
+        fromRewards,
+        shortfall: debt - fromPrincipal - fromRewards,
+        toOwner: principal - fromPrincipal + rewards - fromRewards,
+      };
+    }
+
+    it("Should repay the vault from principal and pay the owner the rest", () => {
+      // $1000 principal, $600 loan plus $30 interest, $150 unclaimed rewards
+      const settled = settle(1000_000000, 150_000000, 630_000000);
+      expect(settled.fromPrincipal).to.equal(630_000000);
+      expect(settled.fromRewards).to.equal(0);
+      expect(settled.toOwner).to.equal(520_000000);
+    });
+
+    it("Should dip into rewards once the debt exceeds principal", () => {
+      const settled = settle(1000_000000, 150_000000, 1100_000000);
+      expect(settled.fromRewards).to.equal(100_000000);
+      expect(settled.shortfall).to.equal(0);
+      expect(settled.toOwner).to.equal(50_000000);
+    });
+
+    it("Should report a shortfall when principal and rewards fall short", () => {
+      const settled = settle(1000_000000, 0, 1200_000000);
+      expect(settled.shortfall).to.equal(200_000000);
+      expect(settled.toOwner).to.equal(0);
+    });
+  });
+
   describe("Time Calculations", () => {
     it("Should calculate lock periods correctly", () => {
       const secondsPerDay = 86400;
@@ -2,24 +2,43 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SolanaContracts } from "../target/types/solana_contracts";
 import { expect } from "chai";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  configPda,
+  createPool,
+  createUsdtMint,
+  applyBoost,
+  deposit,
+  fundTokenAccount,
+  poolPdas,
+  setupBoostToken,
+  userPositionsPda,
+  wexelMintPda,
+  wexelNftAccount,
+  wexelPda,
+} from "./helpers";
 
 describe("Deposit and Boost Tests", () => {
   // Configure the client to use the local cluster.
   anchor.setProvider(anchor.AnchorProvider.env());
 
   const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
-  const provider = anchor.getProvider();
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
 
   // Test accounts
   let user: anchor.web3.Keypair;
   let poolId: anchor.BN;
   let wexelId: anchor.BN;
+  let firstWexelPda: anchor.web3.PublicKey;
+  let usdtMint: anchor.web3.PublicKey;
+  let userTokenAccount: anchor.web3.PublicKey;
+  let boost: Awaited<ReturnType<typeof setupBoostToken>>;
+  let userBoostTokenAccount: anchor.web3.PublicKey;
 
   before(async () => {
     // Generate test keypairs
     user = anchor.web3.Keypair.generate();
     poolId = new anchor.BN(1);
-    wexelId = poolId; // For simplicity, using same ID
 
     // Airdrop SOL to user for transaction fees
     const signature = await provider.connection.requestAirdrop(
@@ -30,40 +49,34 @@ describe("Deposit and Boost Tests", () => {
 
     // Wait for confirmation
     await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    // Fund the user with test USDT for deposits
+    usdtMint = await createUsdtMint(provider);
+    userTokenAccount = await fundTokenAccount(provider, usdtMint, user.publicKey, 100_000_000000);
+
+    // Boost coin priced at $1, so boost amounts below read as USD values
+    boost = await setupBoostToken(program, provider);
+    userBoostTokenAccount = await fundTokenAccount(provider, boost.mint, user.publicKey, 10_000_000000);
+
+    // Pools are created by the admin (provider wallet) before anyone can deposit
+    for (const id of [1, 2, 3, 4, 5]) {
+      await createPool(program, provider, new anchor.BN(id), usdtMint);
+    }
   });
 
   describe("Deposit Functionality", () => {
     it("Should deposit USDT successfully and emit WexelCreated event", async () => {
       const principalUsd = new anchor.BN(1000_000000); // $1000 USDT (6 decimals)
 
-      // Derive PDAs
-      const [poolPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), poolId.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
       // Execute deposit
-      const tx = await program.methods
-        .deposit(poolId, principalUsd)
-        .accounts({
-          user: user.publicKey,
-          pool: poolPda,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
-
-      console.log("✓ Deposit transaction signature:", tx);
+      const result = await deposit(program, poolId, principalUsd, user.publicKey, userTokenAccount, [user]);
+      firstWexelPda = result.wexel;
+      wexelId = result.wexelId;
 
       // Fetch and verify wexel account
-      const wexelAccount = await program.account.wexel.fetch(wexelPda);
-      expect(wexelAccount.id.toString()).to.equal(poolId.toString());
+      const wexelAccount = await program.account.wexel.fetch(firstWexelPda);
+      expect(wexelAccount.id.toNumber()).to.be.greaterThan(0);
+      expect(wexelAccount.poolId.toString()).to.equal(poolId.toString());
       expect(wexelAccount.owner.toString()).to.equal(user.publicKey.toString());
       expect(wexelAccount.principalUsd.toString()).to.equal(principalUsd.toString());
       expect(wexelAccount.apyBp).to.equal(1800); // 18% APY
@@ -73,54 +86,118 @@ describe("Deposit and Boost Tests", () => {
       expect(wexelAccount.isFinalized).to.equal(false);
 
       // Fetch and verify pool account
-      const poolAccount = await program.account.pool.fetch(poolPda);
+      const { pool, poolVault } = poolPdas(program.programId, poolId);
+      const poolAccount = await program.account.pool.fetch(pool);
       expect(poolAccount.id.toString()).to.equal(poolId.toString());
       expect(poolAccount.totalDeposits.toString()).to.equal(principalUsd.toString());
-      expect(poolAccount.apyBp).to.equal(1800);
+      expect(poolAccount.lockTerms[0].apyBp).to.equal(1800);
+
+      // Principal must now sit in the pool vault
+      const vault = await getAccount(provider.connection, poolVault);
+      expect(vault.amount.toString()).to.equal(principalUsd.toString());
+      expect(poolAccount.usdtMint.toBase58()).to.equal(usdtMint.toBase58());
 
       console.log("✓ Wexel created successfully with principal:", principalUsd.toString());
     });
 
+    it("Should allow several wexels for the same user in the same pool", async () => {
+      const principalUsd = new anchor.BN(500_000000);
+
+      const second = await deposit(program, poolId, principalUsd, user.publicKey, userTokenAccount, [user]);
+      const third = await deposit(program, poolId, principalUsd, user.publicKey, userTokenAccount, [user]);
+
+      expect(second.wexel.toBase58()).to.not.equal(firstWexelPda.toBase58());
+      expect(third.wexelId.toNumber()).to.be.greaterThan(second.wexelId.toNumber());
+      expect(second.wexelId.toNumber()).to.be.greaterThan(wexelId.toNumber());
+
+      const positions = await program.account.userPositions.fetch(
+        userPositionsPda(program.programId, user.publicKey)
+      );
+      expect(positions.wexelCount.toNumber()).to.equal(3);
+
+      console.log("✓ Multiple wexels created with unique ids");
+    });
+
     it("Should fail deposit with zero amount", async () => {
       const invalidAmount = new anchor.BN(0);
       const poolId2 = new anchor.BN(2);
 
-      const [poolPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), poolId2.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
+      try {
+        await deposit(program, poolId2, invalidAmount, user.publicKey, userTokenAccount, [user]);
 
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId2.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
+        expect.fail("Should have failed with InvalidAmount error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidAmount");
+        console.log("✓ Correctly rejected zero amount deposit");
+      }
+    });
+
+    it("Should fail deposit into a pool that was not created by the admin", async () => {
+      const unknownPoolId = new anchor.BN(999);
+      const { pool, poolVault } = poolPdas(program.programId, unknownPoolId);
+      const wexel = wexelPda(program.programId, user.publicKey, 3);
 
       try {
         await program.methods
-          .deposit(poolId2, invalidAmount)
+          .deposit(unknownPoolId, new anchor.BN(1000_000000), 12)
           .accounts({
             user: user.publicKey,
-            pool: poolPda,
-            wexel: wexelPda,
+            pool,
+            poolVault,
+            userTokenAccount,
+            config: configPda(program.programId),
+            userPositions: userPositionsPda(program.programId, user.publicKey),
+            wexel,
+            wexelMint: wexelMintPda(program.programId, wexel),
+            wexelNftAccount: wexelNftAccount(program.programId, wexel, user.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            token2022Program: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([user])
           .rpc();
 
-        expect.fail("Should have failed with InvalidAmount error");
+        expect.fail("Should have failed because the pool does not exist");
       } catch (error: any) {
-        expect(error.error.errorCode.code).to.equal("InvalidAmount");
-        console.log("✓ Correctly rejected zero amount deposit");
+        expect(error.error.errorCode.code).to.equal("AccountNotInitialized");
+        console.log("✓ Correctly rejected deposit into a missing pool");
       }
     });
 
-    it("Should verify wexel maturity date is set correctly", async () => {
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId.toArrayLike(Buffer, "le", 8)],
-        program.programId
+    it("Should price longer locks from the pool's term schedule", async () => {
+      const { wexel } = await deposit(
+        program,
+        poolId,
+        new anchor.BN(1000_000000),
+        user.publicKey,
+        userTokenAccount,
+        [user],
+        36
       );
 
-      const wexelAccount = await program.account.wexel.fetch(wexelPda);
+      const wexelAccount = await program.account.wexel.fetch(wexel);
+      expect(wexelAccount.lockPeriodMonths).to.equal(36);
+      expect(wexelAccount.apyBp).to.equal(3600);
+
+      const lockPeriodSeconds = 36 * 30 * 86400;
+      expect(wexelAccount.maturedAt.toNumber() - wexelAccount.createdAt.toNumber()).to.equal(lockPeriodSeconds);
+      console.log("✓ 36-month lock earns the 36% tier");
+    });
+
+    it("Should fail deposit with a lock period the pool does not offer", async () => {
+      try {
+        await deposit(program, poolId, new anchor.BN(1000_000000), user.publicKey, userTokenAccount, [user], 13);
+
+        expect.fail("Should have failed with InvalidLockPeriod error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidLockPeriod");
+        console.log("✓ Correctly rejected unsupported lock period");
+      }
+    });
+
+    it("Should verify wexel maturity date is set correctly", async () => {
+      const wexelAccount = await program.account.wexel.fetch(firstWexelPda);
       const lockPeriodSeconds = 12 * 30 * 86400; // 12 months in seconds
       const expectedMaturity = wexelAccount.createdAt.toNumber() + lockPeriodSeconds;
 
@@ -133,30 +210,26 @@ describe("Deposit and Boost Tests", () => {
     it("Should apply boost successfully and emit BoostApplied event", async () => {
       const boostAmount = new anchor.BN(300_000000); // $300 (30% of $1000 = max boost)
 
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
       // Get wexel before boost
-      const wexelBefore = await program.account.wexel.fetch(wexelPda);
+      const wexelBefore = await program.account.wexel.fetch(firstWexelPda);
       expect(wexelBefore.apyBoostBp).to.equal(0);
 
       // Apply boost
-      const tx = await program.methods
-        .applyBoost(wexelId, boostAmount)
-        .accounts({
-          user: user.publicKey,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
+      const tx = await applyBoost(
+        program,
+        boost,
+        [boost.priceAccount.publicKey],
+        firstWexelPda,
+        wexelId,
+        boostAmount,
+        user,
+        userBoostTokenAccount
+      );
 
       console.log("✓ Apply boost transaction signature:", tx);
 
       // Fetch and verify wexel account
-      const wexelAfter = await program.account.wexel.fetch(wexelPda);
+      const wexelAfter = await program.account.wexel.fetch(firstWexelPda);
 
       // Verify boost APY calculation
       // boost_target = 1000 * 3000 / 10000 = 300
@@ -173,40 +246,29 @@ describe("Deposit and Boost Tests", () => {
       const partialBoostAmount = new anchor.BN(150_000000); // $150 (50% of target)
 
       // Create new deposit
-      const [poolPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), poolId3.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId3.toArrayLike(Buffer, "le", 8)],
-        program.programId
+      const { wexel, wexelId: id } = await deposit(
+        program,
+        poolId3,
+        principalUsd,
+        user.publicKey,
+        userTokenAccount,
+        [user]
       );
 
-      await program.methods
-        .deposit(poolId3, principalUsd)
-        .accounts({
-          user: user.publicKey,
-          pool: poolPda,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
-
       // Apply partial boost
-      await program.methods
-        .applyBoost(poolId3, partialBoostAmount)
-        .accounts({
-          user: user.publicKey,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
+      await applyBoost(
+        program,
+        boost,
+        [boost.priceAccount.publicKey],
+        wexel,
+        id,
+        partialBoostAmount,
+        user,
+        userBoostTokenAccount
+      );
 
       // Verify partial boost
-      const wexelAccount = await program.account.wexel.fetch(wexelPda);
+      const wexelAccount = await program.account.wexel.fetch(wexel);
 
       // boost_ratio = 150 / 300 = 0.5
       // boost_apy_bp = 0.5 * 500 = 250 (2.5%)
@@ -221,40 +283,29 @@ describe("Deposit and Boost Tests", () => {
       const excessiveBoostAmount = new anchor.BN(500_000000); // $500 (exceeds 30% target)
 
       // Create new deposit
-      const [poolPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), poolId4.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId4.toArrayLike(Buffer, "le", 8)],
-        program.programId
+      const { wexel, wexelId: id } = await deposit(
+        program,
+        poolId4,
+        principalUsd,
+        user.publicKey,
+        userTokenAccount,
+        [user]
       );
 
-      await program.methods
-        .deposit(poolId4, principalUsd)
-        .accounts({
-          user: user.publicKey,
-          pool: poolPda,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
-
       // Apply excessive boost
-      await program.methods
-        .applyBoost(poolId4, excessiveBoostAmount)
-        .accounts({
-          user: user.publicKey,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
+      await applyBoost(
+        program,
+        boost,
+        [boost.priceAccount.publicKey],
+        wexel,
+        id,
+        excessiveBoostAmount,
+        user,
+        userBoostTokenAccount
+      );
 
       // Verify boost is capped at maximum
-      const wexelAccount = await program.account.wexel.fetch(wexelPda);
+      const wexelAccount = await program.account.wexel.fetch(wexel);
 
       // boost_ratio = min(500, 300) / 300 = 1.0
       // boost_apy_bp = 1.0 * 500 = 500 (5%)
@@ -266,21 +317,17 @@ describe("Deposit and Boost Tests", () => {
     it("Should fail boost with zero amount", async () => {
       const invalidBoostAmount = new anchor.BN(0);
 
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
       try {
-        await program.methods
-          .applyBoost(wexelId, invalidBoostAmount)
-          .accounts({
-            user: user.publicKey,
-            wexel: wexelPda,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .signers([user])
-          .rpc();
+        await applyBoost(
+          program,
+          boost,
+          [boost.priceAccount.publicKey],
+          firstWexelPda,
+          wexelId,
+          invalidBoostAmount,
+          user,
+          userBoostTokenAccount
+        );
 
         expect.fail("Should have failed with InvalidAmount error");
       } catch (error: any) {
@@ -300,22 +347,24 @@ describe("Deposit and Boost Tests", () => {
       );
       await provider.connection.confirmTransaction(signature);
       await new Promise((resolve) => setTimeout(resolve, 1000));
-
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId.toArrayLike(Buffer, "le", 8)],
-        program.programId
+      const unauthorizedBoostAccount = await fundTokenAccount(
+        provider,
+        boost.mint,
+        unauthorizedUser.publicKey,
+        100_000000
       );
 
       try {
-        await program.methods
-          .applyBoost(wexelId, boostAmount)
-          .accounts({
-            user: unauthorizedUser.publicKey,
-            wexel: wexelPda,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .signers([unauthorizedUser])
-          .rpc();
+        await applyBoost(
+          program,
+          boost,
+          [boost.priceAccount.publicKey],
+          firstWexelPda,
+          wexelId,
+          boostAmount,
+          unauthorizedUser,
+          await fundTokenAccount(provider, boost.mint, unauthorizedUser.publicKey, 0),
+        );
 
         expect.fail("Should have failed with Unauthorized error");
       } catch (error: any) {
@@ -332,46 +381,34 @@ describe("Deposit and Boost Tests", () => {
       const principalUsd = new anchor.BN(5000_000000); // $5000
       const boostAmount = new anchor.BN(1500_000000); // $1500 (30% of $5000)
 
-      // Derive PDAs
-      const [poolPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("pool"), poolId5.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
-      const [wexelPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("wexel"), user.publicKey.toBuffer(), poolId5.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
       // Step 1: Deposit
-      await program.methods
-        .deposit(poolId5, principalUsd)
-        .accounts({
-          user: user.publicKey,
-          pool: poolPda,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
+      const { wexel, wexelId: id } = await deposit(
+        program,
+        poolId5,
+        principalUsd,
+        user.publicKey,
+        userTokenAccount,
+        [user]
+      );
 
       console.log("✓ Step 1: Deposit completed");
 
       // Step 2: Apply boost
-      await program.methods
-        .applyBoost(poolId5, boostAmount)
-        .accounts({
-          user: user.publicKey,
-          wexel: wexelPda,
-          systemProgram: anchor.web3.SystemProgram.programId,
-        })
-        .signers([user])
-        .rpc();
+      await applyBoost(
+        program,
+        boost,
+        [boost.priceAccount.publicKey],
+        wexel,
+        id,
+        boostAmount,
+        user,
+        userBoostTokenAccount
+      );
 
       console.log("✓ Step 2: Boost applied");
 
       // Verify final state
-      const wexelAccount = await program.account.wexel.fetch(wexelPda);
+      const wexelAccount = await program.account.wexel.fetch(wexel);
       expect(wexelAccount.principalUsd.toString()).to.equal(principalUsd.toString());
       expect(wexelAccount.apyBp).to.equal(1800); // 18% base APY
       expect(wexelAccount.apyBoostBp).to.equal(500); // 5% boost APY
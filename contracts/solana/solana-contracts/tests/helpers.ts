@@ -0,0 +1,848 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { TakaraToken } from "../target/types/takara_token";
+import SolanaContractsIdl from "../target/idl/solana_contracts.json";
+import {
+  ACCOUNT_SIZE,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  AccountLayout,
+  AccountState,
+  MINT_SIZE,
+  MintLayout,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+
+export const USD_DECIMALS = 6;
+// Mirrors MAX_ACCEPTED_MINTS in the program
+export const MAX_ACCEPTED_MINTS = 8;
+
+// Default term -> APY schedule used by test pools (18/24/30/36% tiers)
+export const DEFAULT_LOCK_TERMS = [
+  { months: 12, apyBp: 1800 },
+  { months: 18, apyBp: 2400 },
+  { months: 24, apyBp: 3000 },
+  { months: 36, apyBp: 3600 },
+];
+
+// Creates a 6-decimal stand-in for USDT owned by the provider wallet
+export async function createUsdtMint(provider: anchor.AnchorProvider): Promise<anchor.web3.PublicKey> {
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  return createMint(provider.connection, payer, payer.publicKey, null, USD_DECIMALS);
+}
+
+// Creates (or reuses) the owner's associated token account and mints `amount` into it
+export async function fundTokenAccount(
+  provider: anchor.AnchorProvider,
+  mint: anchor.web3.PublicKey,
+  owner: anchor.web3.PublicKey,
+  amount: number | bigint
+): Promise<anchor.web3.PublicKey> {
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const account = await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, owner);
+  if (BigInt(amount) > BigInt(0)) {
+    await mintTo(provider.connection, payer, mint, account.address, payer, amount);
+  }
+  return account.address;
+}
+
+export function poolPdas(programId: anchor.web3.PublicKey, poolId: anchor.BN) {
+  const [pool] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("pool"), poolId.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+  const [poolVault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_vault"), pool.toBuffer()],
+    programId
+  );
+  return { pool, poolVault };
+}
+
+export function configPda(programId: anchor.web3.PublicKey): anchor.web3.PublicKey {
+  return anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("config")], programId)[0];
+}
+
+// Creates the global config with the provider wallet as admin, unless it already exists
+export async function ensureConfig(program: Program<SolanaContracts>, provider: anchor.AnchorProvider) {
+  const config = configPda(program.programId);
+  if ((await provider.connection.getAccountInfo(config)) === null) {
+    await program.methods
+      .initialize(provider.wallet.publicKey, [], new anchor.BN(0))
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+  return config;
+}
+
+// Adds `mint` to the accepted mints as the provider wallet (must be the config admin),
+// dropping the oldest entry when the list is full
+export async function acceptMint(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  mint: anchor.web3.PublicKey
+) {
+  const config = await ensureConfig(program, provider);
+  const { acceptedMints } = await program.account.globalConfig.fetch(config);
+  if (acceptedMints.some((accepted) => accepted.equals(mint))) {
+    return;
+  }
+
+  await program.methods
+    .setAcceptedMints([...acceptedMints, mint].slice(-MAX_ACCEPTED_MINTS))
+    .accounts({ admin: provider.wallet.publicKey, config })
+    .rpc();
+}
+
+// Creates an active pool as the provider wallet (must be the config admin)
+export async function createPool(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  poolId: anchor.BN,
+  usdtMint: anchor.web3.PublicKey,
+  opts: {
+    lockTerms?: { months: number; apyBp: number }[];
+    minDepositUsd?: number;
+    maxDepositUsd?: number;
+    borrowRateBp?: number;
+  } = {}
+) {
+  const config = await ensureConfig(program, provider);
+  await acceptMint(program, provider, usdtMint);
+  const { pool, poolVault } = poolPdas(program.programId, poolId);
+
+  await program.methods
+    .createPool(
+      poolId,
+      opts.lockTerms ?? DEFAULT_LOCK_TERMS,
+      new anchor.BN(opts.minDepositUsd ?? 1_000000),
+      new anchor.BN(opts.maxDepositUsd ?? 1_000_000_000000),
+      opts.borrowRateBp ?? 0
+    )
+    .accounts({
+      admin: provider.wallet.publicKey,
+      config,
+      usdtMint,
+      pool,
+      poolVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  return { pool, poolVault };
+}
+
+export function userPositionsPda(programId: anchor.web3.PublicKey, owner: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("user_positions"), owner.toBuffer()],
+    programId
+  )[0];
+}
+
+export function wexelPda(programId: anchor.web3.PublicKey, owner: anchor.web3.PublicKey, ownerIndex: number) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("wexel"), owner.toBuffer(), new anchor.BN(ownerIndex).toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+export function wexelMintPda(programId: anchor.web3.PublicKey, wexel: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("wexel_mint"), wexel.toBuffer()], programId)[0];
+}
+
+// The holder's Token-2022 associated token account for a wexel NFT
+export function wexelNftAccount(
+  programId: anchor.web3.PublicKey,
+  wexel: anchor.web3.PublicKey,
+  holder: anchor.web3.PublicKey
+) {
+  return getAssociatedTokenAddressSync(wexelMintPda(programId, wexel), holder, false, TOKEN_2022_PROGRAM_ID);
+}
+
+// Deposits into an existing pool and returns the new wexel PDA, its global id and NFT accounts
+export async function deposit(
+  program: Program<SolanaContracts>,
+  poolId: anchor.BN,
+  principalUsd: anchor.BN,
+  owner: anchor.web3.PublicKey,
+  userTokenAccount: anchor.web3.PublicKey,
+  signers: anchor.web3.Keypair[] = [],
+  lockPeriodMonths = 12
+) {
+  const userPositions = userPositionsPda(program.programId, owner);
+  const positions = await program.account.userPositions.fetchNullable(userPositions);
+  const wexel = wexelPda(program.programId, owner, positions ? positions.wexelCount.toNumber() : 0);
+  const { pool, poolVault } = poolPdas(program.programId, poolId);
+  const nftMint = wexelMintPda(program.programId, wexel);
+  const nftAccount = wexelNftAccount(program.programId, wexel, owner);
+
+  await program.methods
+    .deposit(poolId, principalUsd, lockPeriodMonths)
+    .accounts({
+      user: owner,
+      pool,
+      poolVault,
+      userTokenAccount,
+      config: configPda(program.programId),
+      userPositions,
+      wexel,
+      wexelMint: nftMint,
+      wexelNftAccount: nftAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .signers(signers)
+    .rpc();
+
+  const wexelId = (await program.account.wexel.fetch(wexel)).id;
+  return { wexel, wexelId, nftMint, nftAccount };
+}
+
+export function rewardsVaultPdas(programId: anchor.web3.PublicKey, poolId: anchor.BN) {
+  const [rewardsVault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("rewards_vault"), poolId.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+  const [rewardsTokenVault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("rewards_token_vault"), rewardsVault.toBuffer()],
+    programId
+  );
+  return { rewardsVault, rewardsTokenVault };
+}
+
+// Creates the pool's shared rewards vault and funds it from the admin's token account
+export async function initializeRewardsVault(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  poolId: anchor.BN,
+  usdtMint: anchor.web3.PublicKey,
+  fundAmount = 0
+) {
+  const { pool } = poolPdas(program.programId, poolId);
+  const { rewardsVault, rewardsTokenVault } = rewardsVaultPdas(program.programId, poolId);
+  const config = configPda(program.programId);
+
+  await program.methods
+    .initializeRewardsVault(poolId)
+    .accounts({
+      admin: provider.wallet.publicKey,
+      config,
+      pool,
+      usdtMint,
+      rewardsVault,
+      rewardsTokenVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  if (fundAmount > 0) {
+    const authorityTokenAccount = await fundTokenAccount(provider, usdtMint, provider.wallet.publicKey, fundAmount);
+    await fundRewardsVault(program, provider, poolId, authorityTokenAccount, fundAmount);
+  }
+
+  return { rewardsVault, rewardsTokenVault };
+}
+
+// Moves `amount` from the provider wallet's token account into the pool's rewards vault
+export async function fundRewardsVault(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  poolId: anchor.BN,
+  authorityTokenAccount: anchor.web3.PublicKey,
+  amount: number
+) {
+  const { rewardsVault, rewardsTokenVault } = rewardsVaultPdas(program.programId, poolId);
+  await program.methods
+    .fundRewardsVault(poolId, new anchor.BN(amount))
+    .accounts({
+      authority: provider.wallet.publicKey,
+      config: configPda(program.programId),
+      rewardsVault,
+      rewardsTokenVault,
+      authorityTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+// Size of a Pyth v2 price account as read by the program's oracle module
+export const PRICE_ACCOUNT_SIZE = 240;
+
+// First bytes of the mock price write, handled by the program's fallback in `mock-oracle` builds
+const WRITE_MOCK_PRICE_DISCRIMINATOR = Buffer.from("mockpxwr");
+
+// Writes a Pyth-layout price into a mock price account (program built with `mock-oracle`).
+// The instruction is not in the IDL, so it is encoded by hand: (u64, u64, i32, i64).
+export async function writeMockPrice(
+  program: Program<SolanaContracts>,
+  priceAccount: anchor.web3.Keypair,
+  price: number,
+  opts: { expo?: number; conf?: number; publishTime?: number } = {}
+) {
+  const args = Buffer.alloc(28);
+  args.writeBigUInt64LE(BigInt(price), 0);
+  args.writeBigUInt64LE(BigInt(opts.conf ?? 0), 8);
+  args.writeInt32LE(opts.expo ?? -8, 16);
+  args.writeBigInt64LE(BigInt(opts.publishTime ?? Math.floor(Date.now() / 1000)), 20);
+
+  const ix = new anchor.web3.TransactionInstruction({
+    programId: program.programId,
+    keys: [{ pubkey: priceAccount.publicKey, isSigner: true, isWritable: true }],
+    data: Buffer.concat([WRITE_MOCK_PRICE_DISCRIMINATOR, args]),
+  });
+  const provider = program.provider as anchor.AnchorProvider;
+  await provider.sendAndConfirm(new anchor.web3.Transaction().add(ix), [priceAccount]);
+}
+
+// Creates a program-owned mock price account and writes `price * 10^expo` USD into it
+export async function createMockPriceAccount(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  price: number,
+  opts: { expo?: number; conf?: number; publishTime?: number } = {}
+): Promise<anchor.web3.Keypair> {
+  const priceAccount = anchor.web3.Keypair.generate();
+  const lamports = await provider.connection.getMinimumBalanceForRentExemption(PRICE_ACCOUNT_SIZE);
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: provider.wallet.publicKey,
+      newAccountPubkey: priceAccount.publicKey,
+      lamports,
+      space: PRICE_ACCOUNT_SIZE,
+      programId: program.programId,
+    })
+  );
+  await provider.sendAndConfirm(tx, [priceAccount]);
+  await writeMockPrice(program, priceAccount, price, opts);
+  return priceAccount;
+}
+
+export function priceOraclePda(programId: anchor.web3.PublicKey, mint: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("price_oracle"), mint.toBuffer()], programId)[0];
+}
+
+// Creates the price proxy for `mint` reading from `sources`
+export async function initializePriceOracle(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  mint: anchor.web3.PublicKey,
+  sources: anchor.web3.PublicKey[],
+  opts: { maxAgeSecs?: number; maxConfBp?: number } = {}
+) {
+  const priceOracle = priceOraclePda(program.programId, mint);
+  await program.methods
+    .initializePriceOracle(sources, new anchor.BN(opts.maxAgeSecs ?? 60), opts.maxConfBp ?? 200)
+    .accounts({
+      admin: provider.wallet.publicKey,
+      config: await ensureConfig(program, provider),
+      mint,
+      priceOracle,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+  return priceOracle;
+}
+
+export function boostTokenPdas(programId: anchor.web3.PublicKey, mint: anchor.web3.PublicKey) {
+  const [boostToken] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("boost_token"), mint.toBuffer()],
+    programId
+  );
+  const [boostVault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("boost_vault"), mint.toBuffer()],
+    programId
+  );
+  return { boostToken, boostVault };
+}
+
+// Creates a 6-decimal boost coin and whitelists it. Without explicit `sources`
+// it is priced at $1 by one fresh mock source.
+export async function setupBoostToken(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  opts: { sources?: anchor.web3.PublicKey[]; maxAgeSecs?: number; maxConfBp?: number } = {}
+) {
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const mint = await createMint(provider.connection, payer, payer.publicKey, null, USD_DECIMALS);
+  const priceAccount = await createMockPriceAccount(program, provider, 100_000_000, { expo: -8 });
+  const priceOracle = await initializePriceOracle(
+    program,
+    provider,
+    mint,
+    opts.sources ?? [priceAccount.publicKey],
+    { maxAgeSecs: opts.maxAgeSecs ?? 3600, maxConfBp: opts.maxConfBp }
+  );
+  const { boostToken, boostVault } = boostTokenPdas(program.programId, mint);
+
+  await program.methods
+    .addBoostToken()
+    .accounts({
+      admin: provider.wallet.publicKey,
+      config: configPda(program.programId),
+      boostMint: mint,
+      priceOracle,
+      boostToken,
+      boostVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  return { mint, priceAccount, priceOracle, boostToken, boostVault };
+}
+
+// Applies `amount` of a whitelisted boost coin, passing the oracle sources as remaining accounts
+export async function applyBoost(
+  program: Program<SolanaContracts>,
+  boost: {
+    priceOracle: anchor.web3.PublicKey;
+    boostToken: anchor.web3.PublicKey;
+    boostVault: anchor.web3.PublicKey;
+  },
+  sources: anchor.web3.PublicKey[],
+  wexel: anchor.web3.PublicKey,
+  wexelId: anchor.BN,
+  amount: anchor.BN,
+  user: anchor.web3.Keypair,
+  userBoostTokenAccount: anchor.web3.PublicKey
+) {
+  return program.methods
+    .applyBoost(wexelId, amount)
+    .accounts({
+      user: user.publicKey,
+      wexel,
+      wexelNftAccount: wexelNftAccount(program.programId, wexel, user.publicKey),
+      boostToken: boost.boostToken,
+      boostVault: boost.boostVault,
+      userBoostTokenAccount,
+      priceOracle: boost.priceOracle,
+      wexelMint: wexelMintPda(program.programId, wexel),
+      config: configPda(program.programId),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .remainingAccounts(sources.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+    .signers([user])
+    .rpc();
+}
+
+export function listingPda(programId: anchor.web3.PublicKey, wexelId: anchor.BN) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("listing"), wexelId.toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+// Role variants in declaration order; the index is the role's PDA seed byte
+export const ROLES = ["admin", "pauser", "oracle", "treasurer", "keeper"] as const;
+export type RoleName = (typeof ROLES)[number];
+
+export function rolePda(programId: anchor.web3.PublicKey, role: RoleName, holder: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("role"), Buffer.from([ROLES.indexOf(role)]), holder.toBuffer()],
+    programId
+  )[0];
+}
+
+// Grants `role` to `holder` as the provider wallet (must be the config admin); returns the role PDA
+export async function grantRole(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  role: RoleName,
+  holder: anchor.web3.PublicKey
+) {
+  const roleAssignment = rolePda(program.programId, role, holder);
+  await program.methods
+    .grantRole({ [role]: {} } as any, holder)
+    .accounts({
+      authority: provider.wallet.publicKey,
+      config: await ensureConfig(program, provider),
+      roleAssignment,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+  return roleAssignment;
+}
+
+export async function revokeRole(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  role: RoleName,
+  holder: anchor.web3.PublicKey
+) {
+  await program.methods
+    .revokeRole({ [role]: {} } as any, holder)
+    .accounts({
+      authority: provider.wallet.publicKey,
+      grantedBy: provider.wallet.publicKey,
+      config: configPda(program.programId),
+      roleAssignment: rolePda(program.programId, role, holder),
+    })
+    .rpc();
+}
+
+export function multisigPda(programId: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("multisig")], programId)[0];
+}
+
+// Data-less PDA the multisig acts as; roles are handed to this key
+export function multisigSignerPda(programId: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("multisig_signer")], programId)[0];
+}
+
+export function proposalPda(programId: anchor.web3.PublicKey, proposalId: anchor.BN) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("proposal"), proposalId.toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+// Current unix time on the validator, read from the Clock sysvar
+export async function chainTime(provider: anchor.AnchorProvider): Promise<number> {
+  const clock = await provider.connection.getAccountInfo(anchor.web3.SYSVAR_CLOCK_PUBKEY);
+  return Number(clock!.data.readBigInt64LE(32));
+}
+
+export function paramChangePda(programId: anchor.web3.PublicKey, changeId: anchor.BN) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("param_change"), changeId.toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+// Queues `change` as the provider wallet (must be the config admin); returns its id
+export async function scheduleParamChange(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  change: any,
+  eta: anchor.BN
+): Promise<anchor.BN> {
+  const config = await ensureConfig(program, provider);
+  const { paramChangeCount } = await program.account.globalConfig.fetch(config);
+  const changeId = paramChangeCount.addn(1);
+
+  await program.methods
+    .scheduleParamChange(change, eta)
+    .accounts({
+      admin: provider.wallet.publicKey,
+      config,
+      queuedChange: paramChangePda(program.programId, changeId),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+  return changeId;
+}
+
+export async function executeParamChange(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  changeId: anchor.BN
+) {
+  const queuedChange = paramChangePda(program.programId, changeId);
+  const { scheduledBy } = await program.account.queuedParamChange.fetch(queuedChange);
+
+  await program.methods
+    .executeParamChange(changeId)
+    .accounts({
+      executor: provider.wallet.publicKey,
+      scheduledBy,
+      config: configPda(program.programId),
+      queuedChange,
+    })
+    .rpc();
+}
+
+// Schedules and executes `change` at once; relies on the test config's zero timelock delay
+export async function changeParam(program: Program<SolanaContracts>, provider: anchor.AnchorProvider, change: any) {
+  const eta = new anchor.BN(await chainTime(provider));
+  const changeId = await scheduleParamChange(program, provider, change, eta);
+  await executeParamChange(program, provider, changeId);
+}
+
+// Sets the marketplace fee and treasury as the provider wallet (must be the config admin)
+export async function setMarketplaceFee(
+  program: Program<SolanaContracts>,
+  provider: anchor.AnchorProvider,
+  feeBp: number,
+  treasury: anchor.web3.PublicKey
+) {
+  await changeParam(program, provider, { marketplaceFeeBp: { 0: feeBp } });
+  await program.methods
+    .setTreasury(treasury)
+    .accounts({
+      authority: provider.wallet.publicKey,
+      config: await ensureConfig(program, provider),
+    })
+    .rpc();
+}
+
+// Lists a wexel held by `seller` at a fixed price
+export async function listWexel(
+  program: Program<SolanaContracts>,
+  wexel: anchor.web3.PublicKey,
+  wexelId: anchor.BN,
+  nftMint: anchor.web3.PublicKey,
+  price: anchor.BN,
+  paymentMint: anchor.web3.PublicKey,
+  seller: anchor.web3.Keypair
+) {
+  const listing = listingPda(program.programId, wexelId);
+  await program.methods
+    .listWexel(wexelId, price)
+    .accounts({
+      seller: seller.publicKey,
+      wexel,
+      sellerNftAccount: getAssociatedTokenAddressSync(nftMint, seller.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      wexelMint: nftMint,
+      paymentMint,
+      listing,
+      escrowNftAccount: getAssociatedTokenAddressSync(nftMint, listing, true, TOKEN_2022_PROGRAM_ID),
+      config: configPda(program.programId),
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([seller])
+    .rpc();
+  return listing;
+}
+
+// Lists a wexel held by `seller` by Dutch auction
+export async function listWexelDutch(
+  program: Program<SolanaContracts>,
+  wexel: anchor.web3.PublicKey,
+  wexelId: anchor.BN,
+  nftMint: anchor.web3.PublicKey,
+  auction: { startPrice: anchor.BN; floorPrice: anchor.BN; startTime: anchor.BN; endTime: anchor.BN },
+  paymentMint: anchor.web3.PublicKey,
+  seller: anchor.web3.Keypair
+) {
+  const listing = listingPda(program.programId, wexelId);
+  await program.methods
+    .listWexelDutch(wexelId, auction)
+    .accounts({
+      seller: seller.publicKey,
+      wexel,
+      sellerNftAccount: getAssociatedTokenAddressSync(nftMint, seller.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      wexelMint: nftMint,
+      paymentMint,
+      listing,
+      escrowNftAccount: getAssociatedTokenAddressSync(nftMint, listing, true, TOKEN_2022_PROGRAM_ID),
+      config: configPda(program.programId),
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([seller])
+    .rpc();
+  return listing;
+}
+
+// Buys a listed wexel for at most `maxPrice` (the listed price by default) and returns the buyer's new wexel PDA
+export async function buyWexel(
+  program: Program<SolanaContracts>,
+  wexelId: anchor.BN,
+  buyer: anchor.web3.Keypair,
+  accounts: {
+    buyerPaymentAccount: anchor.web3.PublicKey;
+    sellerPaymentAccount: anchor.web3.PublicKey;
+    treasuryPaymentAccount: anchor.web3.PublicKey;
+  },
+  maxPrice?: anchor.BN
+) {
+  const listing = listingPda(program.programId, wexelId);
+  const listingAccount = await program.account.listing.fetch(listing);
+  const buyerPositions = userPositionsPda(program.programId, buyer.publicKey);
+  const positions = await program.account.userPositions.fetchNullable(buyerPositions);
+  const newWexel = wexelPda(program.programId, buyer.publicKey, positions ? positions.wexelCount.toNumber() : 0);
+  const nftMint = listingAccount.nftMint;
+
+  await program.methods
+    .buyWexel(wexelId, maxPrice ?? listingAccount.price)
+    .accounts({
+      buyer: buyer.publicKey,
+      seller: listingAccount.seller,
+      listing,
+      wexel: listingAccount.wexel,
+      wexelMint: nftMint,
+      escrowNftAccount: getAssociatedTokenAddressSync(nftMint, listing, true, TOKEN_2022_PROGRAM_ID),
+      buyerNftAccount: getAssociatedTokenAddressSync(nftMint, buyer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      ...accounts,
+      config: configPda(program.programId),
+      buyerPositions,
+      newWexel,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([buyer])
+    .rpc();
+
+  return newWexel;
+}
+
+export function takaraConfigPda(programId: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("takara_config")], programId)[0];
+}
+
+// Initializes the Takara config with the provider wallet as authority unless it already exists
+export async function ensureTakaraConfig(program: Program<TakaraToken>, provider: anchor.AnchorProvider) {
+  const takaraConfig = takaraConfigPda(program.programId);
+  if ((await provider.connection.getAccountInfo(takaraConfig)) === null) {
+    const takaraMint = anchor.web3.Keypair.generate();
+    const [miningVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mining_vault"), takaraMint.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .initialize(new anchor.BN(1_000_000_000000), "Takara", "TKR")
+      .accounts({
+        authority: provider.wallet.publicKey,
+        takaraMint: takaraMint.publicKey,
+        takaraConfig,
+        miningVault,
+        adminTokenAccount: getAssociatedTokenAddressSync(takaraMint.publicKey, provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([takaraMint])
+      .rpc();
+  }
+  return takaraConfig;
+}
+
+// Claims `amount` Takara for the provider wallet with `oracle` co-signing; pass
+// `oracleRole` when the co-signer holds the oracle role instead of the oracle authority
+export async function claimTakara(
+  program: Program<TakaraToken>,
+  provider: anchor.AnchorProvider,
+  oracle: anchor.web3.Keypair,
+  oracleRole: anchor.web3.PublicKey | null,
+  amount: number,
+  depositId: anchor.BN,
+  nonce: anchor.BN
+) {
+  const takaraConfig = takaraConfigPda(program.programId);
+  const { tokenMint, miningVault } = await program.account.takaraConfig.fetch(takaraConfig);
+  const user = provider.wallet.publicKey;
+  const [claimRecord] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("claim"), depositId.toArrayLike(Buffer, "le", 8), nonce.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+  const userTokenAccount = getAssociatedTokenAddressSync(tokenMint, user);
+
+  await program.methods
+    .claimTakara(new anchor.BN(amount), depositId, nonce)
+    .accounts({
+      user,
+      oracle: oracle.publicKey,
+      takaraConfig,
+      oracleRole,
+      miningVault,
+      userTokenAccount,
+      takaraMint: tokenMint,
+      claimRecord,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([oracle])
+    .rpc();
+
+  return { claimRecord, userTokenAccount };
+}
+
+// Starts an in-process bank with the workspace programs, for tests that move the clock.
+// The helpers above only need the provider's wallet, sendAndConfirm and account reads,
+// which the bankrun provider supports, so it is handed out as an AnchorProvider.
+export async function startBank() {
+  const context = await startAnchor(".", [], []);
+  const provider = new BankrunProvider(context);
+  const program = new Program<SolanaContracts>(SolanaContractsIdl as SolanaContracts, provider);
+  return { context, program, provider: provider as unknown as anchor.AnchorProvider };
+}
+
+// Moves the bank's clock forward by `seconds`
+export async function warpForward(context: ProgramTestContext, seconds: number) {
+  const clock = await context.banksClient.getClock();
+  context.setClock(
+    new Clock(
+      clock.slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      clock.unixTimestamp + BigInt(seconds)
+    )
+  );
+}
+
+// Writes a 6-decimal USDT stand-in and `owner`'s associated token account holding
+// `amount` straight into the bank; spl-token's send helpers don't run on bankrun
+export function setUsdtAccounts(context: ProgramTestContext, owner: anchor.web3.PublicKey, amount: number) {
+  const mint = anchor.web3.Keypair.generate().publicKey;
+  const mintData = Buffer.alloc(MINT_SIZE);
+  MintLayout.encode(
+    {
+      mintAuthorityOption: 1,
+      mintAuthority: owner,
+      supply: BigInt(amount),
+      decimals: USD_DECIMALS,
+      isInitialized: true,
+      freezeAuthorityOption: 0,
+      freezeAuthority: anchor.web3.PublicKey.default,
+    },
+    mintData
+  );
+  context.setAccount(mint, {
+    lamports: anchor.web3.LAMPORTS_PER_SOL,
+    data: mintData,
+    owner: TOKEN_PROGRAM_ID,
+    executable: false,
+  });
+
+  const tokenAccount = getAssociatedTokenAddressSync(mint, owner);
+  const accountData = Buffer.alloc(ACCOUNT_SIZE);
+  AccountLayout.encode(
+    {
+      mint,
+      owner,
+      amount: BigInt(amount),
+      delegateOption: 0,
+      delegate: anchor.web3.PublicKey.default,
+      state: AccountState.Initialized,
+      isNativeOption: 0,
+      isNative: BigInt(0),
+      delegatedAmount: BigInt(0),
+      closeAuthorityOption: 0,
+      closeAuthority: anchor.web3.PublicKey.default,
+    },
+    accountData
+  );
+  context.setAccount(tokenAccount, {
+    lamports: anchor.web3.LAMPORTS_PER_SOL,
+    data: accountData,
+    owner: TOKEN_PROGRAM_ID,
+    executable: false,
+  });
+
+  return { usdtMint: mint, tokenAccount };
+}
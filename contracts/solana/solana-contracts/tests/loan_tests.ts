@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  changeParam,
+  configPda,
+  createPool,
+  createUsdtMint,
+  deposit,
+  ensureConfig,
+  fundTokenAccount,
+  wexelMintPda,
+  wexelNftAccount,
+} from "./helpers";
+
+describe("Loan Disbursement and Repayment Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const user = provider.wallet;
+
+  let usdtMint: anchor.web3.PublicKey;
+  let userTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    usdtMint = await createUsdtMint(provider);
+    userTokenAccount = await fundTokenAccount(provider, usdtMint, user.publicKey, 10_000_000000);
+  });
+
+  async function depositAndCollateralize(poolId: anchor.BN, principalUsd: anchor.BN, borrowRateBp = 0) {
+    const { pool, poolVault } = await createPool(program, provider, poolId, usdtMint, { borrowRateBp });
+    const { wexel, wexelId } = await deposit(program, poolId, principalUsd, user.publicKey, userTokenAccount);
+    const [collateralPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), wexelId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .collateralize(wexelId)
+      .accounts({
+        user: user.publicKey,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user.publicKey),
+        collateralPosition,
+        pool,
+        poolVault,
+        userTokenAccount,
+        wexelMint: wexelMintPda(program.programId, wexel),
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return { pool, poolVault, wexel, wexelId, collateralPosition };
+  }
+
+  it("should disburse 60% of principal from the pool vault", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    const principalUsd = new anchor.BN(1000_000000);
+
+    const { pool, poolVault } = await depositAndCollateralize(poolId, principalUsd);
+
+    const vault = await getAccount(provider.connection, poolVault);
+    expect(vault.amount.toString()).to.equal("400000000");
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    expect(poolAccount.totalLoans.toString()).to.equal("600000000");
+  });
+
+  it("should pull the loan back into the vault on repayment", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    const principalUsd = new anchor.BN(1000_000000);
+
+    const { pool, poolVault, wexel, wexelId, collateralPosition } = await depositAndCollateralize(
+      poolId,
+      principalUsd
+    );
+
+    await program.methods
+      .repayLoan(wexelId, new anchor.BN(600_000000))
+      .accounts({
+        user: user.publicKey,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user.publicKey),
+        collateralPosition,
+        pool,
+        poolVault,
+        userTokenAccount,
+        wexelMint: wexelMintPda(program.programId, wexel),
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const vault = await getAccount(provider.connection, poolVault);
+    expect(vault.amount.toString()).to.equal(principalUsd.toString());
+
+    const poolAccount = await program.account.pool.fetch(pool);
+    expect(poolAccount.totalLoans.toNumber()).to.equal(0);
+
+    const wexelAccount = await program.account.wexel.fetch(wexel);
+    expect(wexelAccount.isCollateralized).to.equal(false);
+    // Pledged time is kept for the yield split at the next accrual
+    expect(wexelAccount.collateralizedAt.toNumber()).to.equal(0);
+    expect(wexelAccount.pendingCollateralSeconds.toNumber()).to.be.at.least(0);
+  });
+
+  async function repay(
+    accounts: Awaited<ReturnType<typeof depositAndCollateralize>>,
+    amount: anchor.BN
+  ) {
+    const { pool, poolVault, wexel, wexelId, collateralPosition } = accounts;
+    await program.methods
+      .repayLoan(wexelId, amount)
+      .accounts({
+        user: user.publicKey,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user.publicKey),
+        collateralPosition,
+        pool,
+        poolVault,
+        userTokenAccount,
+        wexelMint: wexelMintPda(program.programId, wexel),
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  it("should keep the position open after a partial repayment", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    const loan = await depositAndCollateralize(poolId, new anchor.BN(1000_000000));
+
+    await repay(loan, new anchor.BN(200_000000));
+
+    const position = await program.account.collateralPosition.fetch(loan.collateralPosition);
+    expect(position.principalUsd.toString()).to.equal("400000000");
+    expect(position.isRepaid).to.equal(false);
+    const poolAccount = await program.account.pool.fetch(loan.pool);
+    expect(poolAccount.totalLoans.toString()).to.equal("400000000");
+    const wexelAccount = await program.account.wexel.fetch(loan.wexel);
+    expect(wexelAccount.isCollateralized).to.equal(true);
+
+    // Overpaying only pulls the remaining debt and closes the position
+    await repay(loan, new anchor.BN(1000_000000));
+    expect(await provider.connection.getAccountInfo(loan.collateralPosition)).to.be.null;
+    const vault = await getAccount(provider.connection, loan.poolVault);
+    expect(vault.amount.toString()).to.equal("1000000000");
+  });
+
+  it("should charge interest at the pool's borrow rate", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    // 10% a year
+    const loan = await depositAndCollateralize(poolId, new anchor.BN(1000_000000), 1000);
+
+    const position = await program.account.collateralPosition.fetch(loan.collateralPosition);
+    expect(position.borrowRateBp).to.equal(1000);
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await repay(loan, new anchor.BN(700_000000));
+
+    // Principal plus some interest came back to the vault
+    const vault = await getAccount(provider.connection, loan.poolVault);
+    expect(Number(vault.amount)).to.be.greaterThan(1000_000000);
+    expect(await provider.connection.getAccountInfo(loan.collateralPosition)).to.be.null;
+  });
+
+  it("should record when the pledge started", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    const { wexel } = await depositAndCollateralize(poolId, new anchor.BN(1000_000000));
+
+    const wexelAccount = await program.account.wexel.fetch(wexel);
+    expect(wexelAccount.collateralizedAt.toNumber()).to.be.greaterThan(0);
+  });
+
+  it("should let the admin change the collateral yield split", async () => {
+    const config = await ensureConfig(program, provider);
+
+    await changeParam(program, provider, { collateralUserShareBp: { 0: 4000 } });
+
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.collateralUserShareBp).to.equal(4000);
+
+    try {
+      await changeParam(program, provider, { collateralUserShareBp: { 0: 10001 } });
+      expect.fail("Should have failed with InvalidYieldSplit error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidYieldSplit");
+    }
+  });
+});
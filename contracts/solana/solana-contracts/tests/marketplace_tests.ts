@@ -0,0 +1,240 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  buyWexel,
+  configPda,
+  createPool,
+  createUsdtMint,
+  deposit,
+  fundTokenAccount,
+  listWexel,
+  listWexelDutch,
+  listingPda,
+  poolPdas,
+  setMarketplaceFee,
+} from "./helpers";
+
+describe("Marketplace Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  let seller: anchor.web3.Keypair;
+  let buyer: anchor.web3.Keypair;
+  let treasury: anchor.web3.Keypair;
+  let usdtMint: anchor.web3.PublicKey;
+  let sellerTokenAccount: anchor.web3.PublicKey;
+  let buyerTokenAccount: anchor.web3.PublicKey;
+  let treasuryTokenAccount: anchor.web3.PublicKey;
+  let poolId: anchor.BN;
+
+  async function airdrop(to: anchor.web3.PublicKey) {
+    const signature = await provider.connection.requestAirdrop(to, 5 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+  }
+
+  async function newWexel() {
+    return deposit(program, poolId, new anchor.BN(1000_000000), seller.publicKey, sellerTokenAccount, [seller]);
+  }
+
+  before(async () => {
+    seller = anchor.web3.Keypair.generate();
+    buyer = anchor.web3.Keypair.generate();
+    treasury = anchor.web3.Keypair.generate();
+    await airdrop(seller.publicKey);
+    await airdrop(buyer.publicKey);
+
+    usdtMint = await createUsdtMint(provider);
+    sellerTokenAccount = await fundTokenAccount(provider, usdtMint, seller.publicKey, 100_000_000000);
+    buyerTokenAccount = await fundTokenAccount(provider, usdtMint, buyer.publicKey, 100_000_000000);
+    treasuryTokenAccount = await fundTokenAccount(provider, usdtMint, treasury.publicKey, 0);
+    poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+
+    // 2.5% platform fee
+    await setMarketplaceFee(program, provider, 250, treasury.publicKey);
+  });
+
+  it("should escrow the NFT on listing and return it on delisting", async () => {
+    const { wexel, wexelId, nftMint, nftAccount } = await newWexel();
+    const listing = await listWexel(program, wexel, wexelId, nftMint, new anchor.BN(950_000000), usdtMint, seller);
+
+    const escrow = getAssociatedTokenAddressSync(nftMint, listing, true, TOKEN_2022_PROGRAM_ID);
+    const escrowed = await getAccount(provider.connection, escrow, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(escrowed.amount.toString()).to.equal("1");
+
+    await program.methods
+      .delistWexel(wexelId)
+      .accounts({
+        seller: seller.publicKey,
+        listing,
+        wexelMint: nftMint,
+        escrowNftAccount: escrow,
+        sellerNftAccount: nftAccount,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([seller])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(listing)).to.be.null;
+    const returned = await getAccount(provider.connection, nftAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(returned.amount.toString()).to.equal("1");
+  });
+
+  it("should settle a sale atomically with the platform fee", async () => {
+    const { wexel, wexelId, nftMint } = await newWexel();
+    await listWexel(program, wexel, wexelId, nftMint, new anchor.BN(1000_000000), usdtMint, seller);
+
+    const sellerBefore = (await getAccount(provider.connection, sellerTokenAccount)).amount;
+    const newWexelPda = await buyWexel(program, wexelId, buyer, {
+      buyerPaymentAccount: buyerTokenAccount,
+      sellerPaymentAccount: sellerTokenAccount,
+      treasuryPaymentAccount: treasuryTokenAccount,
+    });
+
+    // 2.5% of $1000 to the treasury, the rest to the seller
+    const treasuryAfter = await getAccount(provider.connection, treasuryTokenAccount);
+    expect(treasuryAfter.amount.toString()).to.equal("25000000");
+    const sellerAfter = (await getAccount(provider.connection, sellerTokenAccount)).amount;
+    expect((sellerAfter - sellerBefore).toString()).to.equal("975000000");
+
+    const buyerNft = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(nftMint, buyer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(buyerNft.amount.toString()).to.equal("1");
+
+    const moved = await program.account.wexel.fetch(newWexelPda);
+    expect(moved.owner.toBase58()).to.equal(buyer.publicKey.toBase58());
+    expect(await provider.connection.getAccountInfo(wexel)).to.be.null;
+    expect(await provider.connection.getAccountInfo(listingPda(program.programId, wexelId))).to.be.null;
+  });
+
+  it("should sell a Dutch auction at the decayed price", async () => {
+    const { wexel, wexelId, nftMint } = await newWexel();
+    const now = Math.floor(Date.now() / 1000);
+    // Started long enough ago that the price has reached the floor
+    await listWexelDutch(
+      program,
+      wexel,
+      wexelId,
+      nftMint,
+      {
+        startPrice: new anchor.BN(1000_000000),
+        floorPrice: new anchor.BN(800_000000),
+        startTime: new anchor.BN(now - 7200),
+        endTime: new anchor.BN(now - 3600),
+      },
+      usdtMint,
+      seller
+    );
+
+    const sellerBefore = (await getAccount(provider.connection, sellerTokenAccount)).amount;
+    await buyWexel(
+      program,
+      wexelId,
+      buyer,
+      {
+        buyerPaymentAccount: buyerTokenAccount,
+        sellerPaymentAccount: sellerTokenAccount,
+        treasuryPaymentAccount: treasuryTokenAccount,
+      },
+      new anchor.BN(800_000000)
+    );
+
+    // 2.5% of the $800 floor to the treasury, the rest to the seller
+    const sellerAfter = (await getAccount(provider.connection, sellerTokenAccount)).amount;
+    expect((sellerAfter - sellerBefore).toString()).to.equal("780000000");
+  });
+
+  it("should refuse a purchase above the buyer's max price", async () => {
+    const { wexel, wexelId, nftMint } = await newWexel();
+    const now = Math.floor(Date.now() / 1000);
+    await listWexelDutch(
+      program,
+      wexel,
+      wexelId,
+      nftMint,
+      {
+        startPrice: new anchor.BN(1000_000000),
+        floorPrice: new anchor.BN(800_000000),
+        startTime: new anchor.BN(now - 60),
+        endTime: new anchor.BN(now + 86400),
+      },
+      usdtMint,
+      seller
+    );
+
+    try {
+      await buyWexel(
+        program,
+        wexelId,
+        buyer,
+        {
+          buyerPaymentAccount: buyerTokenAccount,
+          sellerPaymentAccount: sellerTokenAccount,
+          treasuryPaymentAccount: treasuryTokenAccount,
+        },
+        new anchor.BN(800_000000)
+      );
+      expect.fail("Should have failed with SlippageExceeded error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("SlippageExceeded");
+    }
+  });
+
+  it("should refuse to list a collateralized wexel", async () => {
+    const { wexel, wexelId, nftMint, nftAccount } = await newWexel();
+    const { pool, poolVault } = poolPdas(program.programId, poolId);
+    const [collateralPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), wexelId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .collateralize(wexelId)
+      .accounts({
+        user: seller.publicKey,
+        wexel,
+        wexelNftAccount: nftAccount,
+        collateralPosition,
+        pool,
+        poolVault,
+        userTokenAccount: sellerTokenAccount,
+        wexelMint: nftMint,
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([seller])
+      .rpc();
+
+    try {
+      await listWexel(program, wexel, wexelId, nftMint, new anchor.BN(500_000000), usdtMint, seller);
+      expect.fail("Should have failed with WexelAlreadyCollateralized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("WexelAlreadyCollateralized");
+    }
+  });
+
+  it("should refuse fees above the platform cap", async () => {
+    try {
+      await setMarketplaceFee(program, provider, 1001, treasury.publicKey);
+      expect.fail("Should have failed with FeeTooHigh error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("FeeTooHigh");
+    }
+  });
+});
@@ -0,0 +1,313 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  acceptMint,
+  chainTime,
+  createUsdtMint,
+  DEFAULT_LOCK_TERMS,
+  ensureConfig,
+  multisigPda,
+  multisigSignerPda,
+  paramChangePda,
+  poolPdas,
+  proposalPda,
+  setupBoostToken,
+} from "./helpers";
+
+describe("Multisig Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const admin = provider.wallet;
+
+  const signers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+  const THRESHOLD = 2;
+
+  let config: anchor.web3.PublicKey;
+  let multisig: anchor.web3.PublicKey;
+  let multisigSigner: anchor.web3.PublicKey;
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      expect.fail(`Should have failed with ${code} error`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  }
+
+  async function propose(proposer: anchor.web3.Keypair, action: any): Promise<anchor.BN> {
+    const { proposalCount } = await program.account.multisig.fetch(multisig);
+    const proposalId = proposalCount.addn(1);
+    await program.methods
+      .createProposal(action)
+      .accounts({
+        proposer: proposer.publicKey,
+        multisig,
+        proposal: proposalPda(program.programId, proposalId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([proposer])
+      .rpc();
+    return proposalId;
+  }
+
+  async function approve(signer: anchor.web3.Keypair, proposalId: anchor.BN) {
+    await program.methods
+      .approveProposal(proposalId)
+      .accounts({ signer: signer.publicKey, multisig, proposal: proposalPda(program.programId, proposalId) })
+      .signers([signer])
+      .rpc();
+  }
+
+  async function execute(
+    proposalId: anchor.BN,
+    priceOracle: anchor.web3.PublicKey | null = null,
+    remainingAccounts: anchor.web3.AccountMeta[] = []
+  ) {
+    const proposal = proposalPda(program.programId, proposalId);
+    const { proposer } = await program.account.proposal.fetch(proposal);
+    await program.methods
+      .executeProposal(proposalId)
+      .accounts({ executor: admin.publicKey, proposer, multisig, proposal, config, multisigSigner, priceOracle })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+  }
+
+  // Proposes `ix` as an Invoke action, gets it approved and executes it
+  async function invoke(ix: anchor.web3.TransactionInstruction) {
+    const proposalId = await propose(signers[0], {
+      invoke: {
+        accounts: ix.keys.map((key) => ({ pubkey: key.pubkey, isWritable: key.isWritable })),
+        data: ix.data,
+      },
+    });
+    await approve(signers[1], proposalId);
+    // The signer PDA signs inside the program, so no key is passed as a signer
+    await execute(proposalId, null, [
+      ...ix.keys.map((key) => ({ ...key, isSigner: false })),
+      { pubkey: program.programId, isSigner: false, isWritable: false },
+    ]);
+  }
+
+  async function cancel(proposer: anchor.web3.Keypair, proposalId: anchor.BN) {
+    await program.methods
+      .cancelProposal(proposalId)
+      .accounts({ proposer: proposer.publicKey, proposal: proposalPda(program.programId, proposalId) })
+      .signers([proposer])
+      .rpc();
+  }
+
+  before(async () => {
+    config = await ensureConfig(program, provider);
+    multisig = multisigPda(program.programId);
+    multisigSigner = multisigSignerPda(program.programId);
+
+    for (const signer of signers) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(signer.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+      );
+    }
+
+    await program.methods
+      .createMultisig(
+        signers.map((signer) => signer.publicKey),
+        THRESHOLD
+      )
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        multisig,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("should record the signer set", async () => {
+    const multisigAccount = await program.account.multisig.fetch(multisig);
+    expect(multisigAccount.signers.map((key) => key.toString())).to.deep.equal(
+      signers.map((signer) => signer.publicKey.toString())
+    );
+    expect(multisigAccount.threshold).to.equal(THRESHOLD);
+    expect(multisigAccount.signerSetSeqno).to.equal(0);
+  });
+
+  it("should execute an action only once the threshold is reached", async () => {
+    const boost = await setupBoostToken(program, provider);
+
+    // Hand the oracle role to the multisig signer PDA
+    await program.methods
+      .proposeAuthority({ oracle: {} } as any, multisigSigner)
+      .accounts({ admin: admin.publicKey, config })
+      .rpc();
+    const acceptId = await propose(signers[0], { acceptAuthority: { 0: { oracle: {} } } });
+    await expectError(execute(acceptId), "ThresholdNotReached");
+    await approve(signers[1], acceptId);
+    await execute(acceptId);
+
+    let configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.oracleAuthority.toString()).to.equal(multisigSigner.toString());
+    expect(await provider.connection.getAccountInfo(proposalPda(program.programId, acceptId))).to.equal(null);
+
+    const priceId = await propose(signers[2], {
+      setFallbackPrice: { mint: boost.mint, priceUsd: new anchor.BN(2_000000) },
+    });
+    await approve(signers[0], priceId);
+    await execute(priceId, boost.priceOracle);
+
+    const priceOracle = await program.account.priceOracle.fetch(boost.priceOracle);
+    expect(priceOracle.fallbackPriceUsd.toNumber()).to.equal(2_000000);
+
+    // Hand the oracle role back so other suites can keep setting fallback prices
+    await program.methods
+      .proposeAuthority({ oracle: {} } as any, admin.publicKey)
+      .accounts({ admin: admin.publicKey, config })
+      .rpc();
+    await program.methods
+      .acceptAuthority({ oracle: {} } as any)
+      .accounts({ newAuthority: admin.publicKey, config })
+      .rpc();
+    configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.oracleAuthority.toString()).to.equal(admin.publicKey.toString());
+  });
+
+  it("should reach any admin instruction through Invoke once it holds the admin role", async () => {
+    const usdtMint = await createUsdtMint(provider);
+    await acceptMint(program, provider, usdtMint);
+
+    // Hand the admin role over and fund the signer PDA, which pays the rent of what it creates
+    await program.methods
+      .proposeAuthority({ admin: {} } as any, multisigSigner)
+      .accounts({ admin: admin.publicKey, config })
+      .rpc();
+    const acceptId = await propose(signers[0], { acceptAuthority: { 0: { admin: {} } } });
+    await approve(signers[1], acceptId);
+    await execute(acceptId);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: admin.publicKey,
+          toPubkey: multisigSigner,
+          lamports: anchor.web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    // The wallet is no longer the admin
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    const { pool, poolVault } = poolPdas(program.programId, poolId);
+    const createPool = (adminKey: anchor.web3.PublicKey) =>
+      program.methods
+        .createPool(poolId, DEFAULT_LOCK_TERMS, new anchor.BN(1_000000), new anchor.BN(1_000_000_000000), 0)
+        .accounts({
+          admin: adminKey,
+          config,
+          usdtMint,
+          pool,
+          poolVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        });
+    await expectError(createPool(admin.publicKey).rpc(), "Unauthorized");
+
+    await invoke(await createPool(multisigSigner).instruction());
+    const poolAccount = await program.account.pool.fetch(pool);
+    expect(poolAccount.isActive).to.equal(true);
+
+    const { paramChangeCount } = await program.account.globalConfig.fetch(config);
+    const changeId = paramChangeCount.addn(1);
+    const queuedChange = paramChangePda(program.programId, changeId);
+    const eta = new anchor.BN((await chainTime(provider)) + 3600);
+    await invoke(
+      await program.methods
+        .scheduleParamChange({ marketplaceFeeBp: { 0: 100 } }, eta)
+        .accounts({
+          admin: multisigSigner,
+          config,
+          queuedChange,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction()
+    );
+    const queued = await program.account.queuedParamChange.fetch(queuedChange);
+    expect(queued.scheduledBy.toString()).to.equal(multisigSigner.toString());
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.paramChangeCount.toString()).to.equal(changeId.toString());
+
+    // Proposals may not invoke the multisig itself
+    await expectError(
+      propose(signers[0], {
+        invoke: { accounts: [{ pubkey: multisig, isWritable: true }], data: Buffer.alloc(8) },
+      }),
+      "InvalidInvoke"
+    );
+
+    // Hand the admin role back and drop the queued change so other suites keep the wallet as admin
+    const handBackId = await propose(signers[0], {
+      proposeAuthority: { role: { admin: {} }, newAuthority: admin.publicKey },
+    });
+    await approve(signers[1], handBackId);
+    await execute(handBackId);
+    await program.methods
+      .acceptAuthority({ admin: {} } as any)
+      .accounts({ newAuthority: admin.publicKey, config })
+      .rpc();
+    await program.methods
+      .cancelParamChange(changeId)
+      .accounts({ admin: admin.publicKey, scheduledBy: multisigSigner, config, queuedChange })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(queuedChange)).to.equal(null);
+  });
+
+  it("should only act with roles handed to the multisig", async () => {
+    const unpaused = { deposit: false, boost: false, collateralize: false, claim: false, marketplace: false };
+    const proposalId = await propose(signers[0], { setPaused: { 0: { ...unpaused, deposit: true } } });
+    await approve(signers[1], proposalId);
+
+    // The admin is still the provider wallet
+    await expectError(execute(proposalId), "Unauthorized");
+    await cancel(signers[0], proposalId);
+  });
+
+  it("should only let signers propose and approve once each", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const action = { setTreasury: { 0: admin.publicKey } };
+
+    await expectError(propose(stranger, action), "NotMultisigSigner");
+
+    const proposalId = await propose(signers[0], action);
+    await expectError(approve(signers[0], proposalId), "AlreadyApproved");
+    await expectError(approve(stranger, proposalId), "NotMultisigSigner");
+    await expectError(cancel(signers[1], proposalId), "Unauthorized");
+
+    await cancel(signers[0], proposalId);
+  });
+
+  it("should void open proposals when the signer set changes", async () => {
+    const openId = await propose(signers[0], { setTreasury: { 0: admin.publicKey } });
+
+    const newSigners = [signers[0].publicKey, signers[1].publicKey];
+    await expectError(
+      propose(signers[0], { changeSigners: { signers: newSigners, threshold: 3 } }),
+      "InvalidMultisig"
+    );
+
+    const changeId = await propose(signers[0], { changeSigners: { signers: newSigners, threshold: 2 } });
+    await approve(signers[2], changeId);
+    await execute(changeId);
+
+    const multisigAccount = await program.account.multisig.fetch(multisig);
+    expect(multisigAccount.signers.length).to.equal(2);
+    expect(multisigAccount.signerSetSeqno).to.equal(1);
+
+    await expectError(approve(signers[1], openId), "StaleProposal");
+    await cancel(signers[0], openId);
+  });
+});
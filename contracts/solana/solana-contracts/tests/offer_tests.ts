@@ -0,0 +1,237 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  configPda,
+  createPool,
+  createUsdtMint,
+  deposit,
+  fundTokenAccount,
+  setMarketplaceFee,
+  userPositionsPda,
+  wexelPda,
+} from "./helpers";
+
+describe("Offer Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  let seller: anchor.web3.Keypair;
+  let bidder: anchor.web3.Keypair;
+  let treasury: anchor.web3.Keypair;
+  let usdtMint: anchor.web3.PublicKey;
+  let sellerTokenAccount: anchor.web3.PublicKey;
+  let bidderTokenAccount: anchor.web3.PublicKey;
+  let treasuryTokenAccount: anchor.web3.PublicKey;
+  let poolId: anchor.BN;
+  let nextOfferId = 0;
+
+  async function airdrop(to: anchor.web3.PublicKey) {
+    const signature = await provider.connection.requestAirdrop(to, 5 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+  }
+
+  function offerPdas(offerId: anchor.BN) {
+    const [offer] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("offer"), bidder.publicKey.toBuffer(), offerId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [offerEscrow] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("offer_escrow"), offer.toBuffer()],
+      program.programId
+    );
+    return { offer, offerEscrow };
+  }
+
+  async function makeOffer(
+    wexelId: anchor.BN | null,
+    criteria: { poolId: anchor.BN | null; minRemainingDays: number; minApyBp: number; minPrincipalUsd: anchor.BN },
+    amount: anchor.BN,
+    expiresAt: anchor.BN
+  ) {
+    const offerId = new anchor.BN(nextOfferId++);
+    const { offer, offerEscrow } = offerPdas(offerId);
+    await program.methods
+      .makeOffer(offerId, wexelId, criteria, amount, expiresAt)
+      .accounts({
+        bidder: bidder.publicKey,
+        paymentMint: usdtMint,
+        bidderPaymentAccount: bidderTokenAccount,
+        offer,
+        offerEscrow,
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+    return { offer, offerEscrow };
+  }
+
+  async function acceptOffer(offer: anchor.web3.PublicKey, offerEscrow: anchor.web3.PublicKey, wexel: Awaited<ReturnType<typeof deposit>>) {
+    const bidderPositions = userPositionsPda(program.programId, bidder.publicKey);
+    const positions = await program.account.userPositions.fetchNullable(bidderPositions);
+    const newWexel = wexelPda(program.programId, bidder.publicKey, positions ? positions.wexelCount.toNumber() : 0);
+
+    await program.methods
+      .acceptOffer(wexel.wexelId)
+      .accounts({
+        seller: seller.publicKey,
+        bidder: bidder.publicKey,
+        offer,
+        offerEscrow,
+        wexel: wexel.wexel,
+        sellerNftAccount: wexel.nftAccount,
+        wexelMint: wexel.nftMint,
+        bidderNftAccount: getAssociatedTokenAddressSync(wexel.nftMint, bidder.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        sellerPaymentAccount: sellerTokenAccount,
+        treasuryPaymentAccount: treasuryTokenAccount,
+        config: configPda(program.programId),
+        bidderPositions,
+        newWexel,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([seller])
+      .rpc();
+    return newWexel;
+  }
+
+  function inOneHour() {
+    return new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+  }
+
+  const anyWexel = { poolId: null, minRemainingDays: 0, minApyBp: 0, minPrincipalUsd: new anchor.BN(0) };
+
+  before(async () => {
+    seller = anchor.web3.Keypair.generate();
+    bidder = anchor.web3.Keypair.generate();
+    treasury = anchor.web3.Keypair.generate();
+    await airdrop(seller.publicKey);
+    await airdrop(bidder.publicKey);
+
+    usdtMint = await createUsdtMint(provider);
+    sellerTokenAccount = await fundTokenAccount(provider, usdtMint, seller.publicKey, 100_000_000000);
+    bidderTokenAccount = await fundTokenAccount(provider, usdtMint, bidder.publicKey, 100_000_000000);
+    treasuryTokenAccount = await fundTokenAccount(provider, usdtMint, treasury.publicKey, 0);
+    poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+
+    // 2.5% platform fee
+    await setMarketplaceFee(program, provider, 250, treasury.publicKey);
+  });
+
+  it("should escrow the bid and refund it on cancel", async () => {
+    const bidderBefore = (await getAccount(provider.connection, bidderTokenAccount)).amount;
+    const { offer, offerEscrow } = await makeOffer(null, anyWexel, new anchor.BN(500_000000), inOneHour());
+
+    const escrowed = await getAccount(provider.connection, offerEscrow);
+    expect(escrowed.amount.toString()).to.equal("500000000");
+
+    await program.methods
+      .cancelOffer()
+      .accounts({
+        caller: bidder.publicKey,
+        bidder: bidder.publicKey,
+        offer,
+        offerEscrow,
+        bidderPaymentAccount: bidderTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([bidder])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(offer)).to.be.null;
+    const bidderAfter = (await getAccount(provider.connection, bidderTokenAccount)).amount;
+    expect(bidderAfter.toString()).to.equal(bidderBefore.toString());
+  });
+
+  it("should not let a stranger cancel a live offer", async () => {
+    const { offer, offerEscrow } = await makeOffer(null, anyWexel, new anchor.BN(100_000000), inOneHour());
+
+    try {
+      await program.methods
+        .cancelOffer()
+        .accounts({
+          caller: seller.publicKey,
+          bidder: bidder.publicKey,
+          offer,
+          offerEscrow,
+          bidderPaymentAccount: bidderTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+      expect.fail("Should have failed with OfferNotExpired error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("OfferNotExpired");
+    }
+  });
+
+  it("should swap a wexel for a targeted offer with the platform fee", async () => {
+    const wexel = await deposit(program, poolId, new anchor.BN(1000_000000), seller.publicKey, sellerTokenAccount, [seller]);
+    const { offer, offerEscrow } = await makeOffer(wexel.wexelId, anyWexel, new anchor.BN(1000_000000), inOneHour());
+
+    const sellerBefore = (await getAccount(provider.connection, sellerTokenAccount)).amount;
+    const treasuryBefore = (await getAccount(provider.connection, treasuryTokenAccount)).amount;
+    const newWexel = await acceptOffer(offer, offerEscrow, wexel);
+
+    // 2.5% of $1000 to the treasury, the rest to the seller
+    const treasuryAfter = (await getAccount(provider.connection, treasuryTokenAccount)).amount;
+    expect((treasuryAfter - treasuryBefore).toString()).to.equal("25000000");
+    const sellerAfter = (await getAccount(provider.connection, sellerTokenAccount)).amount;
+    expect((sellerAfter - sellerBefore).toString()).to.equal("975000000");
+
+    const moved = await program.account.wexel.fetch(newWexel);
+    expect(moved.owner.toBase58()).to.equal(bidder.publicKey.toBase58());
+    expect(await provider.connection.getAccountInfo(wexel.wexel)).to.be.null;
+    expect(await provider.connection.getAccountInfo(offer)).to.be.null;
+  });
+
+  it("should refuse a wexel that does not meet the offer criteria", async () => {
+    const wexel = await deposit(program, poolId, new anchor.BN(1000_000000), seller.publicKey, sellerTokenAccount, [seller]);
+    const criteria = { ...anyWexel, poolId: poolId.addn(1) };
+    const { offer, offerEscrow } = await makeOffer(null, criteria, new anchor.BN(900_000000), inOneHour());
+
+    try {
+      await acceptOffer(offer, offerEscrow, wexel);
+      expect.fail("Should have failed with OfferCriteriaNotMet error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("OfferCriteriaNotMet");
+    }
+  });
+
+  it("should refuse a wexel below the offer's minimum principal", async () => {
+    const wexel = await deposit(program, poolId, new anchor.BN(10_000000), seller.publicKey, sellerTokenAccount, [seller]);
+    const criteria = { ...anyWexel, minPrincipalUsd: new anchor.BN(5000_000000) };
+    const { offer, offerEscrow } = await makeOffer(null, criteria, new anchor.BN(5000_000000), inOneHour());
+
+    try {
+      await acceptOffer(offer, offerEscrow, wexel);
+      expect.fail("Should have failed with OfferCriteriaNotMet error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("OfferCriteriaNotMet");
+    }
+  });
+
+  it("should refuse an expiry in the past", async () => {
+    try {
+      await makeOffer(null, anyWexel, new anchor.BN(100_000000), new anchor.BN(Math.floor(Date.now() / 1000) - 60));
+      expect.fail("Should have failed with InvalidExpiry error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidExpiry");
+    }
+  });
+});
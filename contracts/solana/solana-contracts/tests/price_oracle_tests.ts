@@ -0,0 +1,218 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  applyBoost,
+  configPda,
+  createMockPriceAccount,
+  createPool,
+  createUsdtMint,
+  deposit,
+  fundTokenAccount,
+  PRICE_ACCOUNT_SIZE,
+  setupBoostToken,
+} from "./helpers";
+
+// Prices are written with expo -8, so 1_00000000 is $1.00
+const DOLLAR = 100_000_000;
+
+describe("Price Oracle Proxy Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  let user: anchor.web3.Keypair;
+  let poolId: anchor.BN;
+  let userTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    user = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(user.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+
+    const usdtMint = await createUsdtMint(provider);
+    userTokenAccount = await fundTokenAccount(provider, usdtMint, user.publicKey, 100_000_000000);
+    poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+  });
+
+  // Boosts a fresh $10,000 wexel with 100 boost coins and returns the USD value credited
+  async function boostValue(
+    boost: Awaited<ReturnType<typeof setupBoostToken>>,
+    sources: anchor.web3.PublicKey[]
+  ): Promise<number> {
+    const { wexel, wexelId } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(10_000_000000),
+      user.publicKey,
+      userTokenAccount,
+      [user]
+    );
+    const userBoostTokenAccount = await fundTokenAccount(provider, boost.mint, user.publicKey, 100_000000);
+    await applyBoost(program, boost, sources, wexel, wexelId, new anchor.BN(100_000000), user, userBoostTokenAccount);
+    return (await program.account.wexel.fetch(wexel)).boostValueUsd.toNumber();
+  }
+
+  it("should price at the median of the configured sources", async () => {
+    const low = await createMockPriceAccount(program, provider, 98_000_000);
+    const mid = await createMockPriceAccount(program, provider, DOLLAR);
+    const high = await createMockPriceAccount(program, provider, 5 * DOLLAR);
+    const sources = [low.publicKey, mid.publicKey, high.publicKey];
+    const boost = await setupBoostToken(program, provider, { sources });
+
+    // 100 coins at the $1.00 median
+    expect(await boostValue(boost, sources)).to.equal(100_000000);
+  });
+
+  it("should ignore stale and low-confidence sources", async () => {
+    const fresh = await createMockPriceAccount(program, provider, 2 * DOLLAR);
+    const stale = await createMockPriceAccount(program, provider, 9 * DOLLAR, {
+      publishTime: Math.floor(Date.now() / 1000) - 7200,
+    });
+    // 10% confidence interval against a 2% limit
+    const uncertain = await createMockPriceAccount(program, provider, 7 * DOLLAR, { conf: 70_000_000 });
+    const sources = [fresh.publicKey, stale.publicKey, uncertain.publicKey];
+    const boost = await setupBoostToken(program, provider, { sources, maxAgeSecs: 600, maxConfBp: 200 });
+
+    expect(await boostValue(boost, sources)).to.equal(200_000000);
+  });
+
+  it("should fall back to the admin price when no source is usable", async () => {
+    const stale = await createMockPriceAccount(program, provider, 3 * DOLLAR, {
+      publishTime: Math.floor(Date.now() / 1000) - 7200,
+    });
+    const sources = [stale.publicKey];
+    const boost = await setupBoostToken(program, provider, { sources, maxAgeSecs: 600 });
+
+    try {
+      await boostValue(boost, sources);
+      expect.fail("Should have failed with NoValidPrice error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("NoValidPrice");
+    }
+
+    // $1.50 per whole token, in micro-USD
+    await program.methods
+      .setFallbackPrice(new anchor.BN(1_500000))
+      .accounts({
+        oracle: provider.wallet.publicKey,
+        config: configPda(program.programId),
+        priceOracle: boost.priceOracle,
+      })
+      .rpc();
+
+    expect(await boostValue(boost, sources)).to.equal(150_000000);
+  });
+
+  it("should not serve a fallback price older than the max age", async () => {
+    const stale = await createMockPriceAccount(program, provider, 3 * DOLLAR, {
+      publishTime: Math.floor(Date.now() / 1000) - 7200,
+    });
+    const sources = [stale.publicKey];
+    const boost = await setupBoostToken(program, provider, { sources, maxAgeSecs: 1 });
+
+    await program.methods
+      .setFallbackPrice(new anchor.BN(1_500000))
+      .accounts({
+        oracle: provider.wallet.publicKey,
+        config: configPda(program.programId),
+        priceOracle: boost.priceOracle,
+      })
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    try {
+      await boostValue(boost, sources);
+      expect.fail("Should have failed with NoValidPrice error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("NoValidPrice");
+    }
+  });
+
+  it("should ignore prices published in the future", async () => {
+    const fresh = await createMockPriceAccount(program, provider, 2 * DOLLAR);
+    const future = await createMockPriceAccount(program, provider, 9 * DOLLAR, {
+      publishTime: Math.floor(Date.now() / 1000) + 7200,
+    });
+    const sources = [fresh.publicKey, future.publicKey];
+    const boost = await setupBoostToken(program, provider, { sources });
+
+    expect(await boostValue(boost, sources)).to.equal(200_000000);
+  });
+
+  it("should require every pinned source", async () => {
+    const low = await createMockPriceAccount(program, provider, DOLLAR);
+    const high = await createMockPriceAccount(program, provider, 5 * DOLLAR);
+    const boost = await setupBoostToken(program, provider, { sources: [low.publicKey, high.publicKey] });
+
+    // Passing only the high source would otherwise price the boost at $5
+    try {
+      await boostValue(boost, [high.publicKey]);
+      expect.fail("Should have failed with MissingPriceSource error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("MissingPriceSource");
+    }
+  });
+
+  it("should reject price accounts that are not pinned on the oracle", async () => {
+    const boost = await setupBoostToken(program, provider);
+    const rogue = await createMockPriceAccount(program, provider, 1000 * DOLLAR);
+
+    try {
+      await boostValue(boost, [rogue.publicKey]);
+      expect.fail("Should have failed with InvalidPriceAccount error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidPriceAccount");
+    }
+  });
+
+  it("should reject pinned sources not owned by the Pyth program", async () => {
+    // A system-owned account with room for a price; its owner is checked before its data
+    const foreign = anchor.web3.Keypair.generate();
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(PRICE_ACCOUNT_SIZE);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: foreign.publicKey,
+          lamports,
+          space: PRICE_ACCOUNT_SIZE,
+          programId: anchor.web3.SystemProgram.programId,
+        })
+      ),
+      [foreign]
+    );
+    const sources = [foreign.publicKey];
+    const boost = await setupBoostToken(program, provider, { sources });
+
+    try {
+      await boostValue(boost, sources);
+      expect.fail("Should have failed with InvalidPriceAccount error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidPriceAccount");
+    }
+  });
+
+  it("should reject oracle updates from anyone but the oracle authority", async () => {
+    const boost = await setupBoostToken(program, provider);
+    const attacker = anchor.web3.Keypair.generate();
+
+    try {
+      await program.methods
+        .setFallbackPrice(new anchor.BN(1_000_000_000000))
+        .accounts({
+          oracle: attacker.publicKey,
+          config: configPda(program.programId),
+          priceOracle: boost.priceOracle,
+        })
+        .signers([attacker])
+        .rpc();
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+  });
+});
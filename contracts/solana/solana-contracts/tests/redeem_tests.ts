@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import {
+  configPda,
+  createPool,
+  deposit,
+  fundRewardsVault,
+  initializeRewardsVault,
+  rewardsVaultPdas,
+  setUsdtAccounts,
+  startBank,
+  warpForward,
+  wexelMintPda,
+  wexelNftAccount,
+} from "./helpers";
+
+// Runs on bankrun: redemption needs the clock moved past maturity
+describe("Redeem Tests", () => {
+  const SECONDS_PER_DAY = 86_400;
+  const SECONDS_PER_YEAR = 365 * SECONDS_PER_DAY;
+  const LOCK_SECONDS = 12 * 30 * SECONDS_PER_DAY;
+
+  let context: ProgramTestContext;
+  let program: Program<SolanaContracts>;
+  let provider: anchor.AnchorProvider;
+  let user: anchor.web3.PublicKey;
+  let usdtMint: anchor.web3.PublicKey;
+  let userTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    ({ context, program, provider } = await startBank());
+    user = provider.wallet.publicKey;
+    ({ usdtMint, tokenAccount: userTokenAccount } = setUsdtAccounts(context, user, 100_000_000000));
+  });
+
+  async function setupPool(poolId: anchor.BN, borrowRateBp = 0) {
+    const { pool, poolVault } = await createPool(program, provider, poolId, usdtMint, { borrowRateBp });
+    await initializeRewardsVault(program, provider, poolId, usdtMint);
+    await fundRewardsVault(program, provider, poolId, userTokenAccount, 1_000_000000);
+    return { pool, poolVault, ...rewardsVaultPdas(program.programId, poolId) };
+  }
+
+  async function finalize(wexel: anchor.web3.PublicKey, wexelId: anchor.BN, rewardsVault: anchor.web3.PublicKey) {
+    await program.methods
+      .mintWexelFinalize(wexelId)
+      .accounts({
+        user,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user),
+        rewardsVault,
+        config: configPda(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function redeem(
+    wexel: anchor.web3.PublicKey,
+    wexelId: anchor.BN,
+    accounts: {
+      pool: anchor.web3.PublicKey;
+      poolVault: anchor.web3.PublicKey;
+      rewardsVault: anchor.web3.PublicKey;
+      rewardsTokenVault: anchor.web3.PublicKey;
+    },
+    collateralPosition: anchor.web3.PublicKey | null = null
+  ) {
+    await program.methods
+      .redeem(wexelId)
+      .accounts({
+        user,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user),
+        ...accounts,
+        collateralPosition,
+        userTokenAccount,
+        wexelMint: wexelMintPda(program.programId, wexel),
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function balance(account: anchor.web3.PublicKey) {
+    return (await getAccount(provider.connection, account)).amount;
+  }
+
+  async function collateralize(
+    wexel: anchor.web3.PublicKey,
+    wexelId: anchor.BN,
+    accounts: { pool: anchor.web3.PublicKey; poolVault: anchor.web3.PublicKey }
+  ) {
+    const [collateralPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), wexelId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .collateralize(wexelId)
+      .accounts({
+        user,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, user),
+        collateralPosition,
+        pool: accounts.pool,
+        poolVault: accounts.poolVault,
+        userTokenAccount,
+        wexelMint: wexelMintPda(program.programId, wexel),
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    return collateralPosition;
+  }
+
+  // Reward or interest on `amount` at `rateBp` over `seconds`, computed like math::accrue_reward
+  function accrued(amount: anchor.BN, rateBp: number, seconds: number) {
+    return amount.muln(rateBp).muln(seconds).divn(10_000).divn(SECONDS_PER_YEAR);
+  }
+
+  it("should return the principal from the pool vault and close the wexel", async () => {
+    const poolId = new anchor.BN(1);
+    const principalUsd = new anchor.BN(1000_000000);
+    const accounts = await setupPool(poolId);
+    const { wexel, wexelId, nftMint } = await deposit(program, poolId, principalUsd, user, userTokenAccount);
+
+    await warpForward(context, LOCK_SECONDS + SECONDS_PER_DAY);
+    await finalize(wexel, wexelId, accounts.rewardsVault);
+    const { apyBp, totalRewards } = await program.account.wexel.fetch(wexel);
+    expect(totalRewards.toString()).to.equal(accrued(principalUsd, apyBp, LOCK_SECONDS).toString());
+
+    const poolVaultBefore = await balance(accounts.poolVault);
+    const rewardsVaultBefore = await balance(accounts.rewardsTokenVault);
+    const userBefore = await balance(userTokenAccount);
+
+    await redeem(wexel, wexelId, accounts);
+
+    expect((poolVaultBefore - (await balance(accounts.poolVault))).toString()).to.equal(principalUsd.toString());
+    expect((rewardsVaultBefore - (await balance(accounts.rewardsTokenVault))).toString()).to.equal(
+      totalRewards.toString()
+    );
+    expect(((await balance(userTokenAccount)) - userBefore).toString()).to.equal(
+      principalUsd.add(totalRewards).toString()
+    );
+
+    expect(await provider.connection.getAccountInfo(wexel)).to.equal(null);
+    expect(await provider.connection.getAccountInfo(nftMint)).to.equal(null);
+    const poolAccount = await program.account.pool.fetch(accounts.pool);
+    expect(poolAccount.totalDeposits.toString()).to.equal("0");
+  });
+
+  it("should settle an open loan out of the principal and split the final yield with the platform", async () => {
+    const poolId = new anchor.BN(2);
+    const principalUsd = new anchor.BN(1000_000000);
+    const borrowRateBp = 1000;
+    const accounts = await setupPool(poolId, borrowRateBp);
+    const { wexel, wexelId } = await deposit(program, poolId, principalUsd, user, userTokenAccount);
+    const collateralPosition = await collateralize(wexel, wexelId, accounts);
+    const { loanUsd } = await program.account.collateralPosition.fetch(collateralPosition);
+
+    // Pledged for the whole lock, so all of its yield is split
+    const elapsed = LOCK_SECONDS + SECONDS_PER_DAY;
+    await warpForward(context, elapsed);
+    await finalize(wexel, wexelId, accounts.rewardsVault);
+
+    const { apyBp, totalRewards } = await program.account.wexel.fetch(wexel);
+    const { collateralUserShareBp } = await program.account.globalConfig.fetch(configPda(program.programId));
+    const reward = accrued(principalUsd, apyBp, LOCK_SECONDS);
+    const platformReward = reward.muln(10_000 - collateralUserShareBp).divn(10_000);
+    const userReward = reward.sub(platformReward);
+    expect(totalRewards.toString()).to.equal(userReward.toString());
+    const vaultAccount = await program.account.rewardsVault.fetch(accounts.rewardsVault);
+    expect(vaultAccount.platformRewards.toString()).to.equal(platformReward.toString());
+
+    const poolVaultBefore = await balance(accounts.poolVault);
+    const userBefore = await balance(userTokenAccount);
+
+    await redeem(wexel, wexelId, accounts, collateralPosition);
+
+    // The debt (loan plus interest up to redemption) stays in the pool vault
+    const debt = loanUsd.add(accrued(loanUsd, borrowRateBp, elapsed));
+    const principalToOwner = principalUsd.sub(debt);
+    expect((poolVaultBefore - (await balance(accounts.poolVault))).toString()).to.equal(principalToOwner.toString());
+    expect(((await balance(userTokenAccount)) - userBefore).toString()).to.equal(
+      principalToOwner.add(userReward).toString()
+    );
+
+    const poolAccount = await program.account.pool.fetch(accounts.pool);
+    expect(poolAccount.totalLoans.toString()).to.equal("0");
+    expect(await provider.connection.getAccountInfo(collateralPosition)).to.equal(null);
+    expect(await provider.connection.getAccountInfo(wexel)).to.equal(null);
+  });
+});
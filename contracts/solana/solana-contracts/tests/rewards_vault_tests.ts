@@ -0,0 +1,84 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import {
+  configPda,
+  createPool,
+  createUsdtMint,
+  fundTokenAccount,
+  initializeRewardsVault,
+  rewardsVaultPdas,
+} from "./helpers";
+
+describe("Pool Rewards Vault Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  let usdtMint: anchor.web3.PublicKey;
+
+  before(async () => {
+    usdtMint = await createUsdtMint(provider);
+  });
+
+  it("should create one shared vault per pool and accept admin funding", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+
+    const { rewardsVault, rewardsTokenVault } = await initializeRewardsVault(
+      program,
+      provider,
+      poolId,
+      usdtMint,
+      5000_000000
+    );
+
+    const vault = await program.account.rewardsVault.fetch(rewardsVault);
+    expect(vault.poolId.toString()).to.equal(poolId.toString());
+    expect(vault.totalFunded.toString()).to.equal("5000000000");
+
+    const tokenVault = await getAccount(provider.connection, rewardsTokenVault);
+    expect(tokenVault.amount.toString()).to.equal("5000000000");
+    expect(tokenVault.owner.toBase58()).to.equal(rewardsVault.toBase58());
+  });
+
+  it("should reject funding from a non-admin signer", async () => {
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+    const { rewardsVault, rewardsTokenVault } = await initializeRewardsVault(program, provider, poolId, usdtMint);
+
+    const stranger = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+    const strangerTokenAccount = await fundTokenAccount(provider, usdtMint, stranger.publicKey, 100_000000);
+
+    try {
+      await program.methods
+        .fundRewardsVault(poolId, new anchor.BN(100_000000))
+        .accounts({
+          authority: stranger.publicKey,
+          config: configPda(program.programId),
+          rewardsVault,
+          rewardsTokenVault,
+          authorityTokenAccount: strangerTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+  });
+
+  it("should derive the same vault for every wexel of a pool", () => {
+    const poolId = new anchor.BN(42);
+    const first = rewardsVaultPdas(program.programId, poolId);
+    const second = rewardsVaultPdas(program.programId, poolId);
+    expect(first.rewardsVault.toBase58()).to.equal(second.rewardsVault.toBase58());
+  });
+});
@@ -0,0 +1,186 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  createPool,
+  createUsdtMint,
+  deposit,
+  ensureConfig,
+  fundTokenAccount,
+  grantRole,
+  initializeRewardsVault,
+  revokeRole,
+  rolePda,
+} from "./helpers";
+
+describe("Role Registry Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const admin = provider.wallet;
+
+  const unpaused = { deposit: false, boost: false, collateralize: false, claim: false, marketplace: false };
+
+  let config: anchor.web3.PublicKey;
+
+  async function setPaused(paused: typeof unpaused, signer?: anchor.web3.Keypair, pauserRole?: anchor.web3.PublicKey) {
+    const builder = program.methods
+      .setPaused(paused)
+      .accounts({ authority: signer ? signer.publicKey : admin.publicKey, config, pauserRole: pauserRole ?? null });
+    await (signer ? builder.signers([signer]) : builder).rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      expect.fail(`Should have failed with ${code} error`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  }
+
+  before(async () => {
+    config = await ensureConfig(program, provider);
+  });
+
+  after(async () => {
+    await setPaused(unpaused);
+  });
+
+  it("should record the role assignment", async () => {
+    const holder = anchor.web3.Keypair.generate();
+    const roleAssignment = await grantRole(program, provider, "keeper", holder.publicKey);
+
+    const assignment = await program.account.roleAssignment.fetch(roleAssignment);
+    expect(assignment.role).to.deep.equal({ keeper: {} });
+    expect(assignment.holder.toString()).to.equal(holder.publicKey.toString());
+    expect(assignment.grantedBy.toString()).to.equal(admin.publicKey.toString());
+
+    await revokeRole(program, provider, "keeper", holder.publicKey);
+    expect(await provider.connection.getAccountInfo(roleAssignment)).to.equal(null);
+  });
+
+  it("should let a pauser pause but not resume", async () => {
+    const pauser = anchor.web3.Keypair.generate();
+    const pauserRole = await grantRole(program, provider, "pauser", pauser.publicKey);
+
+    await setPaused({ ...unpaused, deposit: true }, pauser, pauserRole);
+    let configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.paused.deposit).to.equal(true);
+
+    await expectError(setPaused(unpaused, pauser, pauserRole), "Unauthorized");
+
+    await setPaused(unpaused);
+    configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.paused.deposit).to.equal(false);
+
+    // A revoked pauser loses the switch
+    await revokeRole(program, provider, "pauser", pauser.publicKey);
+    await expectError(setPaused({ ...unpaused, deposit: true }, pauser), "Unauthorized");
+  });
+
+  it("should not let one role stand in for another", async () => {
+    const treasurer = anchor.web3.Keypair.generate();
+    const treasurerRole = await grantRole(program, provider, "treasurer", treasurer.publicKey);
+
+    // The treasurer's assignment is not at the pauser seeds
+    await expectError(
+      setPaused({ ...unpaused, deposit: true }, treasurer, treasurerRole),
+      "ConstraintSeeds"
+    );
+
+    await program.methods
+      .setTreasury(treasurer.publicKey)
+      .accounts({ authority: treasurer.publicKey, config, treasurerRole })
+      .signers([treasurer])
+      .rpc();
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.treasury.toString()).to.equal(treasurer.publicKey.toString());
+
+    await program.methods
+      .setTreasury(admin.publicKey)
+      .accounts({ authority: admin.publicKey, config, treasurerRole: null })
+      .rpc();
+    await revokeRole(program, provider, "treasurer", treasurer.publicKey);
+  });
+
+  it("should let a keeper accrue wexels it does not hold", async () => {
+    const usdtMint = await createUsdtMint(provider);
+    const userTokenAccount = await fundTokenAccount(provider, usdtMint, admin.publicKey, 1_000_000000);
+    const poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+    const { rewardsVault } = await initializeRewardsVault(program, provider, poolId, usdtMint);
+    const { wexel, wexelId, nftAccount } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      admin.publicKey,
+      userTokenAccount
+    );
+
+    const keeper = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(keeper.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const accrue = (keeperRole: anchor.web3.PublicKey | null) =>
+      program.methods
+        .accrue(wexelId)
+        .accounts({
+          user: keeper.publicKey,
+          wexel,
+          wexelNftAccount: nftAccount,
+          rewardsVault,
+          config,
+          keeperRole,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([keeper])
+        .rpc();
+
+    await expectError(accrue(null), "Unauthorized");
+
+    // With the role the call gets past authorization; a fresh wexel has nothing to accrue yet
+    const keeperRole = await grantRole(program, provider, "keeper", keeper.publicKey);
+    await expectError(accrue(keeperRole), "TooEarlyToAccrue");
+
+    await revokeRole(program, provider, "keeper", keeper.publicKey);
+  });
+
+  it("should only let the admin or an admin role holder grant roles", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const grant = (adminRole: anchor.web3.PublicKey | null) =>
+      program.methods
+        .grantRole({ pauser: {} } as any, stranger.publicKey)
+        .accounts({
+          authority: stranger.publicKey,
+          config,
+          adminRole,
+          roleAssignment: rolePda(program.programId, "pauser", stranger.publicKey),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+
+    await expectError(grant(null), "Unauthorized");
+
+    const adminRole = await grantRole(program, provider, "admin", stranger.publicKey);
+    await grant(adminRole);
+
+    await revokeRole(program, provider, "admin", stranger.publicKey);
+    await program.methods
+      .revokeRole({ pauser: {} } as any, stranger.publicKey)
+      .accounts({
+        authority: admin.publicKey,
+        grantedBy: stranger.publicKey,
+        config,
+        adminRole: null,
+        roleAssignment: rolePda(program.programId, "pauser", stranger.publicKey),
+      })
+      .rpc();
+  });
+});
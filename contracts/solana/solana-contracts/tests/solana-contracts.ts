@@ -11,7 +11,7 @@ describe("Solana Contracts - Basic Tests", () => {
   const provider = anchor.getProvider();
 
   it("Should initialize program", async () => {
-    const tx = await program.methods.initialize().rpc();
+    const tx = await program.methods.initialize(provider.publicKey, [], new anchor.BN(0)).rpc();
     console.log("Initialize transaction signature:", tx);
     expect(tx).to.be.a("string");
   });
@@ -0,0 +1,161 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TakaraToken } from "../target/types/takara_token";
+import { expect } from "chai";
+import { ensureTakaraConfig } from "./helpers";
+
+describe("Takara Authority Handover Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.TakaraToken as Program<TakaraToken>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const authority = provider.wallet;
+
+  const ADMIN = { admin: {} };
+  const ORACLE = { oracle: {} };
+
+  let takaraConfig: anchor.web3.PublicKey;
+
+  async function propose(role: object, newAuthority: anchor.web3.PublicKey) {
+    await program.methods
+      .proposeAuthority(role as any, newAuthority)
+      .accounts({ authority: authority.publicKey, takaraConfig })
+      .rpc();
+  }
+
+  async function accept(role: object, signer?: anchor.web3.Keypair) {
+    const builder = program.methods
+      .acceptAuthority(role as any)
+      .accounts({ newAuthority: signer ? signer.publicKey : authority.publicKey, takaraConfig });
+    await (signer ? builder.signers([signer]) : builder).rpc();
+  }
+
+  async function cancel(role: object) {
+    await program.methods
+      .cancelAuthorityProposal(role as any)
+      .accounts({ authority: authority.publicKey, takaraConfig })
+      .rpc();
+  }
+
+  before(async () => {
+    takaraConfig = await ensureTakaraConfig(program, provider);
+  });
+
+  it("should hand the oracle role over only once the new key accepts", async () => {
+    const newOracle = anchor.web3.Keypair.generate();
+
+    await propose(ORACLE, newOracle.publicKey);
+
+    let configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.oracleAuthority.toString()).to.equal(authority.publicKey.toString());
+    expect(configAccount.pendingOracleAuthority.toString()).to.equal(newOracle.publicKey.toString());
+
+    await accept(ORACLE, newOracle);
+
+    configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.oracleAuthority.toString()).to.equal(newOracle.publicKey.toString());
+    expect(configAccount.pendingOracleAuthority.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+
+    // Hand it back so other suites can keep updating the price
+    await propose(ORACLE, authority.publicKey);
+    await accept(ORACLE);
+  });
+
+  it("should only let the proposed key accept", async () => {
+    const proposed = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+
+    await propose(ADMIN, proposed.publicKey);
+
+    try {
+      await accept(ADMIN, stranger);
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    const configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.authority.toString()).to.equal(authority.publicKey.toString());
+
+    await cancel(ADMIN);
+  });
+
+  it("should drop a cancelled proposal", async () => {
+    const proposed = anchor.web3.Keypair.generate();
+
+    await propose(ADMIN, proposed.publicKey);
+    await cancel(ADMIN);
+
+    const configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.pendingAuthority.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+
+    try {
+      await accept(ADMIN, proposed);
+      expect.fail("Should have failed with NoPendingAuthority error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("NoPendingAuthority");
+    }
+
+    try {
+      await cancel(ADMIN);
+      expect.fail("Should have failed with NoPendingAuthority error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("NoPendingAuthority");
+    }
+  });
+
+  it("should refuse to propose the current or default key", async () => {
+    for (const key of [authority.publicKey, anchor.web3.PublicKey.default]) {
+      try {
+        await propose(ADMIN, key);
+        expect.fail("Should have failed with InvalidAuthority error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidAuthority");
+      }
+    }
+  });
+
+  it("should only let the authority propose or cancel", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+
+    try {
+      await program.methods
+        .proposeAuthority(ADMIN as any, stranger.publicKey)
+        .accounts({ authority: stranger.publicKey, takaraConfig })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    await propose(ORACLE, stranger.publicKey);
+    try {
+      await program.methods
+        .cancelAuthorityProposal(ORACLE as any)
+        .accounts({ authority: stranger.publicKey, takaraConfig })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+    await cancel(ORACLE);
+  });
+
+  it("should refuse to migrate a config that already has the current layout", async () => {
+    try {
+      await program.methods
+        .migrateConfig()
+        .accounts({
+          authority: authority.publicKey,
+          takaraConfig,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("Should have failed with ConfigUpToDate error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("ConfigUpToDate");
+    }
+  });
+});
@@ -0,0 +1,349 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TakaraToken } from "../target/types/takara_token";
+import { expect } from "chai";
+import {
+  claimTakara,
+  ensureTakaraConfig,
+  multisigPda,
+  multisigSignerPda,
+  proposalPda,
+  rolePda,
+} from "./helpers";
+
+describe("Takara Multisig Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.TakaraToken as Program<TakaraToken>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const authority = provider.wallet;
+
+  const signers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+  const THRESHOLD = 2;
+
+  let takaraConfig: anchor.web3.PublicKey;
+  let multisig: anchor.web3.PublicKey;
+  let multisigSigner: anchor.web3.PublicKey;
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      expect.fail(`Should have failed with ${code} error`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  }
+
+  function createMultisig(keys: anchor.web3.PublicKey[], threshold: number) {
+    return program.methods
+      .createMultisig(keys, threshold)
+      .accounts({
+        authority: authority.publicKey,
+        takaraConfig,
+        multisig,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function propose(proposer: anchor.web3.Keypair, action: any): Promise<anchor.BN> {
+    const { proposalCount } = await program.account.multisig.fetch(multisig);
+    const proposalId = proposalCount.addn(1);
+    await program.methods
+      .createProposal(action)
+      .accounts({
+        proposer: proposer.publicKey,
+        multisig,
+        proposal: proposalPda(program.programId, proposalId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([proposer])
+      .rpc();
+    return proposalId;
+  }
+
+  async function approve(signer: anchor.web3.Keypair, proposalId: anchor.BN) {
+    await program.methods
+      .approveProposal(proposalId)
+      .accounts({ signer: signer.publicKey, multisig, proposal: proposalPda(program.programId, proposalId) })
+      .signers([signer])
+      .rpc();
+  }
+
+  async function execute(proposalId: anchor.BN, remainingAccounts: anchor.web3.AccountMeta[] = []) {
+    const proposal = proposalPda(program.programId, proposalId);
+    const { proposer } = await program.account.proposal.fetch(proposal);
+    await program.methods
+      .executeProposal(proposalId)
+      .accounts({ executor: authority.publicKey, proposer, multisig, proposal, takaraConfig, multisigSigner })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+  }
+
+  // Proposes `ix` as an Invoke action, gets it approved and executes it
+  async function invoke(ix: anchor.web3.TransactionInstruction) {
+    const proposalId = await propose(signers[0], {
+      invoke: {
+        accounts: ix.keys.map((key) => ({ pubkey: key.pubkey, isWritable: key.isWritable })),
+        data: ix.data,
+      },
+    });
+    await approve(signers[1], proposalId);
+    // The signer PDA signs inside the program, so no key is passed as a signer
+    await execute(proposalId, [
+      ...ix.keys.map((key) => ({ ...key, isSigner: false })),
+      { pubkey: program.programId, isSigner: false, isWritable: false },
+    ]);
+  }
+
+  // Hands `role` to the multisig signer PDA through an executed AcceptAuthority proposal
+  async function handOver(role: "admin" | "oracle") {
+    await program.methods
+      .proposeAuthority({ [role]: {} } as any, multisigSigner)
+      .accounts({ authority: authority.publicKey, takaraConfig })
+      .rpc();
+    const acceptId = await propose(signers[0], { acceptAuthority: { 0: { [role]: {} } } });
+    await approve(signers[1], acceptId);
+    await execute(acceptId);
+  }
+
+  // Hands `role` back to the provider wallet so other suites keep it
+  async function handBack(role: "admin" | "oracle") {
+    const handBackId = await propose(signers[0], {
+      proposeAuthority: { role: { [role]: {} }, newAuthority: authority.publicKey },
+    });
+    await approve(signers[1], handBackId);
+    await execute(handBackId);
+    await program.methods
+      .acceptAuthority({ [role]: {} } as any)
+      .accounts({ newAuthority: authority.publicKey, takaraConfig })
+      .rpc();
+  }
+
+  async function cancel(proposer: anchor.web3.Keypair, proposalId: anchor.BN) {
+    await program.methods
+      .cancelProposal(proposalId)
+      .accounts({ proposer: proposer.publicKey, proposal: proposalPda(program.programId, proposalId) })
+      .signers([proposer])
+      .rpc();
+  }
+
+  before(async () => {
+    takaraConfig = await ensureTakaraConfig(program, provider);
+    multisig = multisigPda(program.programId);
+    multisigSigner = multisigSignerPda(program.programId);
+
+    for (const signer of signers) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(signer.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+      );
+    }
+  });
+
+  it("should only create a multisig with a valid signer set", async () => {
+    const keys = signers.map((signer) => signer.publicKey);
+
+    await expectError(createMultisig([keys[0], keys[0]], 1), "InvalidMultisig");
+    await expectError(createMultisig(keys, 4), "InvalidMultisig");
+    await expectError(createMultisig(keys, 0), "InvalidMultisig");
+
+    await createMultisig(keys, THRESHOLD);
+
+    const multisigAccount = await program.account.multisig.fetch(multisig);
+    expect(multisigAccount.signers.map((key) => key.toString())).to.deep.equal(keys.map((key) => key.toString()));
+    expect(multisigAccount.threshold).to.equal(THRESHOLD);
+    expect(multisigAccount.signerSetSeqno).to.equal(0);
+  });
+
+  it("should execute an action only once the threshold is reached", async () => {
+    // Hand the oracle authority to the multisig signer PDA
+    await program.methods
+      .proposeAuthority({ oracle: {} } as any, multisigSigner)
+      .accounts({ authority: authority.publicKey, takaraConfig })
+      .rpc();
+    const acceptId = await propose(signers[0], { acceptAuthority: { 0: { oracle: {} } } });
+    await expectError(execute(acceptId), "ThresholdNotReached");
+    await approve(signers[1], acceptId);
+    await execute(acceptId);
+
+    let configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.oracleAuthority.toString()).to.equal(multisigSigner.toString());
+    expect(await provider.connection.getAccountInfo(proposalPda(program.programId, acceptId))).to.equal(null);
+
+    const priceId = await propose(signers[2], { updatePrice: { 0: new anchor.BN(420_000) } });
+    await expectError(execute(priceId), "ThresholdNotReached");
+    await approve(signers[0], priceId);
+    await execute(priceId);
+
+    configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.internalPriceUsd.toNumber()).to.equal(420_000);
+
+    // Hand the oracle authority back through the multisig
+    await handBack("oracle");
+    configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.oracleAuthority.toString()).to.equal(authority.publicKey.toString());
+  });
+
+  it("should grant and revoke roles through Invoke once it holds the authority", async () => {
+    // Hand the authority over and fund the signer PDA, which pays the rent of what it creates
+    await handOver("admin");
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: multisigSigner,
+          lamports: anchor.web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    const holder = anchor.web3.Keypair.generate().publicKey;
+    const roleAssignment = rolePda(program.programId, "keeper", holder);
+    const grantRole = (authorityKey: anchor.web3.PublicKey) =>
+      program.methods.grantRole({ keeper: {} } as any, holder).accounts({
+        authority: authorityKey,
+        takaraConfig,
+        adminRole: null,
+        roleAssignment,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      });
+
+    // The wallet is no longer the authority
+    await expectError(grantRole(authority.publicKey).rpc(), "Unauthorized");
+
+    await invoke(await grantRole(multisigSigner).instruction());
+    const assignment = await program.account.roleAssignment.fetch(roleAssignment);
+    expect(assignment.grantedBy.toString()).to.equal(multisigSigner.toString());
+
+    await invoke(
+      await program.methods
+        .revokeRole({ keeper: {} } as any, holder)
+        .accounts({
+          authority: multisigSigner,
+          grantedBy: multisigSigner,
+          takaraConfig,
+          adminRole: null,
+          roleAssignment,
+        })
+        .instruction()
+    );
+    expect(await provider.connection.getAccountInfo(roleAssignment)).to.equal(null);
+
+    // A proposed handover can be withdrawn through Invoke as well
+    const proposeId = await propose(signers[0], {
+      proposeAuthority: { role: { oracle: {} }, newAuthority: holder },
+    });
+    await approve(signers[1], proposeId);
+    await execute(proposeId);
+    await invoke(
+      await program.methods
+        .cancelAuthorityProposal({ oracle: {} } as any)
+        .accounts({ authority: multisigSigner, takaraConfig })
+        .instruction()
+    );
+    let configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.pendingOracleAuthority.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+
+    // Proposals may not invoke the multisig itself
+    await expectError(
+      propose(signers[0], {
+        invoke: { accounts: [{ pubkey: multisig, isWritable: true }], data: Buffer.alloc(8) },
+      }),
+      "InvalidInvoke"
+    );
+
+    await handBack("admin");
+    configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.authority.toString()).to.equal(authority.publicKey.toString());
+  });
+
+  it("should let an oracle role holder co-sign claims after the oracle handover", async () => {
+    await handOver("oracle");
+
+    const oracle = anchor.web3.Keypair.generate();
+    const oracleRole = rolePda(program.programId, "oracle", oracle.publicKey);
+    await program.methods
+      .grantRole({ oracle: {} } as any, oracle.publicKey)
+      .accounts({
+        authority: authority.publicKey,
+        takaraConfig,
+        adminRole: null,
+        roleAssignment: oracleRole,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const depositId = new anchor.BN(Date.now());
+    const { claimRecord } = await claimTakara(program, provider, oracle, oracleRole, 1_000000, depositId, new anchor.BN(1));
+    const record = await program.account.claimRecord.fetch(claimRecord);
+    expect(record.amount.toNumber()).to.equal(1_000000);
+
+    await program.methods
+      .revokeRole({ oracle: {} } as any, oracle.publicKey)
+      .accounts({
+        authority: authority.publicKey,
+        grantedBy: authority.publicKey,
+        takaraConfig,
+        adminRole: null,
+        roleAssignment: oracleRole,
+      })
+      .rpc();
+    await expectError(
+      claimTakara(program, provider, oracle, null, 1_000000, depositId, new anchor.BN(2)),
+      "Unauthorized"
+    );
+
+    await handBack("oracle");
+  });
+
+  it("should only act with roles handed to the multisig", async () => {
+    const proposalId = await propose(signers[0], { setPaused: { 0: true } });
+    await approve(signers[1], proposalId);
+
+    // The authority is still the provider wallet
+    await expectError(execute(proposalId), "Unauthorized");
+    await cancel(signers[0], proposalId);
+  });
+
+  it("should only let signers propose and approve once each", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const action = { updatePrice: { 0: new anchor.BN(100_000) } };
+
+    await expectError(propose(stranger, action), "NotMultisigSigner");
+
+    const proposalId = await propose(signers[0], action);
+    await expectError(approve(signers[0], proposalId), "AlreadyApproved");
+    await approve(signers[1], proposalId);
+    await expectError(approve(signers[1], proposalId), "AlreadyApproved");
+    await expectError(approve(stranger, proposalId), "NotMultisigSigner");
+    await expectError(cancel(signers[1], proposalId), "Unauthorized");
+
+    await cancel(signers[0], proposalId);
+  });
+
+  it("should void open proposals when the signer set changes", async () => {
+    const openId = await propose(signers[0], { updatePrice: { 0: new anchor.BN(100_000) } });
+
+    const newSigners = [signers[0].publicKey, signers[1].publicKey];
+    await expectError(
+      propose(signers[0], { changeSigners: { signers: newSigners, threshold: 3 } }),
+      "InvalidMultisig"
+    );
+
+    const changeId = await propose(signers[0], { changeSigners: { signers: newSigners, threshold: 2 } });
+    await approve(signers[2], changeId);
+    await execute(changeId);
+
+    const multisigAccount = await program.account.multisig.fetch(multisig);
+    expect(multisigAccount.signers.length).to.equal(2);
+    expect(multisigAccount.signerSetSeqno).to.equal(1);
+
+    // Removed signers are out, and approvals of the old set no longer count
+    await expectError(propose(signers[2], { setPaused: { 0: true } }), "NotMultisigSigner");
+    await expectError(approve(signers[1], openId), "StaleProposal");
+    await cancel(signers[0], openId);
+  });
+});
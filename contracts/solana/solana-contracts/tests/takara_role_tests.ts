@@ -0,0 +1,186 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TakaraToken } from "../target/types/takara_token";
+import { expect } from "chai";
+import { getAccount } from "@solana/spl-token";
+import { claimTakara, ensureTakaraConfig, RoleName, rolePda } from "./helpers";
+
+describe("Takara Role Registry Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.TakaraToken as Program<TakaraToken>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const authority = provider.wallet;
+
+  let takaraConfig: anchor.web3.PublicKey;
+
+  async function grantRole(role: RoleName, holder: anchor.web3.PublicKey) {
+    const roleAssignment = rolePda(program.programId, role, holder);
+    await program.methods
+      .grantRole({ [role]: {} } as any, holder)
+      .accounts({
+        authority: authority.publicKey,
+        takaraConfig,
+        adminRole: null,
+        roleAssignment,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    return roleAssignment;
+  }
+
+  async function revokeRole(role: RoleName, holder: anchor.web3.PublicKey) {
+    await program.methods
+      .revokeRole({ [role]: {} } as any, holder)
+      .accounts({
+        authority: authority.publicKey,
+        grantedBy: authority.publicKey,
+        takaraConfig,
+        adminRole: null,
+        roleAssignment: rolePda(program.programId, role, holder),
+      })
+      .rpc();
+  }
+
+  async function setPaused(paused: boolean, signer?: anchor.web3.Keypair, pauserRole?: anchor.web3.PublicKey) {
+    const builder = program.methods
+      .setPaused(paused)
+      .accounts({ authority: signer ? signer.publicKey : authority.publicKey, takaraConfig, pauserRole: pauserRole ?? null });
+    await (signer ? builder.signers([signer]) : builder).rpc();
+  }
+
+  async function updatePrice(price: number, signer: anchor.web3.Keypair, oracleRole: anchor.web3.PublicKey | null) {
+    await program.methods
+      .updatePrice(new anchor.BN(price))
+      .accounts({ oracle: signer.publicKey, takaraConfig, oracleRole })
+      .signers([signer])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      expect.fail(`Should have failed with ${code} error`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  }
+
+  before(async () => {
+    takaraConfig = await ensureTakaraConfig(program, provider);
+  });
+
+  after(async () => {
+    await setPaused(false);
+  });
+
+  it("should record the role assignment and close it on revoke", async () => {
+    const holder = anchor.web3.Keypair.generate();
+    const roleAssignment = await grantRole("keeper", holder.publicKey);
+
+    const assignment = await program.account.roleAssignment.fetch(roleAssignment);
+    expect(assignment.role).to.deep.equal({ keeper: {} });
+    expect(assignment.holder.toString()).to.equal(holder.publicKey.toString());
+    expect(assignment.grantedBy.toString()).to.equal(authority.publicKey.toString());
+
+    await revokeRole("keeper", holder.publicKey);
+    expect(await provider.connection.getAccountInfo(roleAssignment)).to.equal(null);
+  });
+
+  it("should let a pauser pause but not resume", async () => {
+    const pauser = anchor.web3.Keypair.generate();
+    const pauserRole = await grantRole("pauser", pauser.publicKey);
+
+    await setPaused(true, pauser, pauserRole);
+    let configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.isPaused).to.equal(true);
+
+    await expectError(setPaused(false, pauser, pauserRole), "Unauthorized");
+
+    await setPaused(false);
+    configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.isPaused).to.equal(false);
+
+    // A revoked pauser loses the switch
+    await revokeRole("pauser", pauser.publicKey);
+    await expectError(setPaused(true, pauser), "Unauthorized");
+  });
+
+  it("should let an oracle role holder update the price", async () => {
+    const oracle = anchor.web3.Keypair.generate();
+
+    await expectError(updatePrice(250_000, oracle, null), "Unauthorized");
+
+    const oracleRole = await grantRole("oracle", oracle.publicKey);
+    await updatePrice(250_000, oracle, oracleRole);
+    const configAccount = await program.account.takaraConfig.fetch(takaraConfig);
+    expect(configAccount.internalPriceUsd.toNumber()).to.equal(250_000);
+
+    await revokeRole("oracle", oracle.publicKey);
+    await expectError(updatePrice(300_000, oracle, null), "Unauthorized");
+  });
+
+  it("should let an oracle role holder co-sign a claim", async () => {
+    const oracle = anchor.web3.Keypair.generate();
+    const depositId = new anchor.BN(Date.now());
+    const claim = (nonce: number, oracleRole: anchor.web3.PublicKey | null) =>
+      claimTakara(program, provider, oracle, oracleRole, 1_000000, depositId, new anchor.BN(nonce));
+
+    await expectError(claim(1, null), "Unauthorized");
+
+    const oracleRole = await grantRole("oracle", oracle.publicKey);
+    const { claimRecord, userTokenAccount } = await claim(1, oracleRole);
+    const record = await program.account.claimRecord.fetch(claimRecord);
+    expect(record.amount.toNumber()).to.equal(1_000000);
+    expect(Number((await getAccount(provider.connection, userTokenAccount)).amount)).to.be.at.least(1_000000);
+
+    await revokeRole("oracle", oracle.publicKey);
+    await expectError(claim(2, null), "Unauthorized");
+  });
+
+  it("should not let one role stand in for another", async () => {
+    const oracle = anchor.web3.Keypair.generate();
+    const oracleRole = await grantRole("oracle", oracle.publicKey);
+
+    // The oracle's assignment is not at the pauser seeds
+    await expectError(setPaused(true, oracle, oracleRole), "ConstraintSeeds");
+
+    await revokeRole("oracle", oracle.publicKey);
+  });
+
+  it("should only let the authority or an admin role holder grant roles", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const grant = (adminRole: anchor.web3.PublicKey | null) =>
+      program.methods
+        .grantRole({ pauser: {} } as any, stranger.publicKey)
+        .accounts({
+          authority: stranger.publicKey,
+          takaraConfig,
+          adminRole,
+          roleAssignment: rolePda(program.programId, "pauser", stranger.publicKey),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+
+    await expectError(grant(null), "Unauthorized");
+
+    const adminRole = await grantRole("admin", stranger.publicKey);
+    await grant(adminRole);
+
+    await revokeRole("admin", stranger.publicKey);
+    await program.methods
+      .revokeRole({ pauser: {} } as any, stranger.publicKey)
+      .accounts({
+        authority: authority.publicKey,
+        grantedBy: stranger.publicKey,
+        takaraConfig,
+        adminRole: null,
+        roleAssignment: rolePda(program.programId, "pauser", stranger.publicKey),
+      })
+      .rpc();
+  });
+});
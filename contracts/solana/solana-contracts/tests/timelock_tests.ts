@@ -0,0 +1,146 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  chainTime,
+  changeParam,
+  ensureConfig,
+  executeParamChange,
+  paramChangePda,
+  scheduleParamChange,
+} from "./helpers";
+
+describe("Timelocked Parameter Change Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const admin = provider.wallet;
+
+  let config: anchor.web3.PublicKey;
+
+  async function cancel(changeId: anchor.BN) {
+    await program.methods
+      .cancelParamChange(changeId)
+      .accounts({
+        admin: admin.publicKey,
+        scheduledBy: admin.publicKey,
+        config,
+        queuedChange: paramChangePda(program.programId, changeId),
+      })
+      .rpc();
+  }
+
+  before(async () => {
+    config = await ensureConfig(program, provider);
+  });
+
+  it("should start from the default parameters", async () => {
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.ltvBp).to.equal(6000);
+    expect(configAccount.apyTiersBp).to.deep.equal([1800, 2400, 3000, 3600]);
+    expect(configAccount.boostTargetBp).to.equal(3000);
+    expect(configAccount.maxBoostApyBp).to.equal(500);
+  });
+
+  it("should apply a change once its ETA has passed", async () => {
+    await changeParam(program, provider, { ltvBp: { 0: 5000 } });
+
+    let configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.ltvBp).to.equal(5000);
+
+    await changeParam(program, provider, { ltvBp: { 0: 6000 } });
+    configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.ltvBp).to.equal(6000);
+  });
+
+  it("should refuse to execute before the ETA and allow the admin to cancel", async () => {
+    const eta = new anchor.BN((await chainTime(provider)) + 3600);
+    const changeId = await scheduleParamChange(program, provider, { ltvBp: { 0: 1000 } }, eta);
+
+    const queued = await program.account.queuedParamChange.fetch(paramChangePda(program.programId, changeId));
+    expect(queued.eta.toString()).to.equal(eta.toString());
+
+    try {
+      await executeParamChange(program, provider, changeId);
+      expect.fail("Should have failed with ParamChangeNotReady error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("ParamChangeNotReady");
+    }
+
+    await cancel(changeId);
+
+    const configAccount = await program.account.globalConfig.fetch(config);
+    expect(configAccount.ltvBp).to.equal(6000);
+    expect(await provider.connection.getAccountInfo(paramChangePda(program.programId, changeId))).to.equal(null);
+  });
+
+  it("should refuse to cancel a change that is already due", async () => {
+    const eta = new anchor.BN(await chainTime(provider));
+    const changeId = await scheduleParamChange(program, provider, { ltvBp: { 0: 6000 } }, eta);
+
+    try {
+      await cancel(changeId);
+      expect.fail("Should have failed with ParamChangeDue error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("ParamChangeDue");
+    }
+
+    await executeParamChange(program, provider, changeId);
+  });
+
+  it("should refuse an ETA earlier than the configured delay", async () => {
+    const eta = new anchor.BN((await chainTime(provider)) - 60);
+
+    try {
+      await scheduleParamChange(program, provider, { ltvBp: { 0: 5000 } }, eta);
+      expect.fail("Should have failed with EtaTooEarly error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("EtaTooEarly");
+    }
+  });
+
+  it("should validate changes when they are scheduled", async () => {
+    const eta = new anchor.BN((await chainTime(provider)) + 3600);
+    const invalid: [any, string][] = [
+      [{ ltvBp: { 0: 10000 } }, "InvalidParameter"],
+      [{ apyTiersBp: { 0: [1800, 1800, 3000, 3600] } }, "InvalidApy"],
+      [{ marketplaceFeeBp: { 0: 1001 } }, "FeeTooHigh"],
+      [{ delay: { 0: new anchor.BN(31 * 86400) } }, "InvalidDelay"],
+    ];
+
+    for (const [change, code] of invalid) {
+      try {
+        await scheduleParamChange(program, provider, change, eta);
+        expect.fail(`Should have failed with ${code} error`);
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal(code);
+      }
+    }
+  });
+
+  it("should only let the admin schedule changes", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const { paramChangeCount } = await program.account.globalConfig.fetch(config);
+
+    try {
+      await program.methods
+        .scheduleParamChange({ ltvBp: { 0: 9000 } } as any, new anchor.BN((await chainTime(provider)) + 3600))
+        .accounts({
+          admin: stranger.publicKey,
+          config,
+          queuedChange: paramChangePda(program.programId, paramChangeCount.addn(1)),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have failed with Unauthorized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+  });
+});
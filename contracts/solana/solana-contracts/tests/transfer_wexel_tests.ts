@@ -0,0 +1,194 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+} from "@solana/spl-token";
+import {
+  configPda,
+  createPool,
+  createUsdtMint,
+  deposit,
+  fundTokenAccount,
+  poolPdas,
+  userPositionsPda,
+  wexelMintPda,
+  wexelNftAccount,
+  wexelPda,
+} from "./helpers";
+
+describe("Wexel Transfer Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  let owner: anchor.web3.Keypair;
+  let usdtMint: anchor.web3.PublicKey;
+  let ownerTokenAccount: anchor.web3.PublicKey;
+  let poolId: anchor.BN;
+
+  async function airdrop(to: anchor.web3.PublicKey) {
+    const signature = await provider.connection.requestAirdrop(to, 5 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+  }
+
+  // Transfers a wexel held by `from` to `to` and returns the new record PDA
+  async function transferWexel(
+    wexel: anchor.web3.PublicKey,
+    wexelId: anchor.BN,
+    from: anchor.web3.Keypair,
+    to: anchor.web3.PublicKey
+  ) {
+    const newOwnerPositions = userPositionsPda(program.programId, to);
+    const positions = await program.account.userPositions.fetchNullable(newOwnerPositions);
+    const newWexel = wexelPda(program.programId, to, positions ? positions.wexelCount.toNumber() : 0);
+    const nftMint = (await program.account.wexel.fetch(wexel)).nftMint;
+
+    await program.methods
+      .transferWexel(wexelId)
+      .accounts({
+        owner: from.publicKey,
+        newOwner: to,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, from.publicKey),
+        wexelMint: nftMint,
+        newOwnerNftAccount: wexelNftAccount(program.programId, wexel, to),
+        newOwnerPositions,
+        newWexel,
+        config: configPda(program.programId),
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([from])
+      .rpc();
+
+    return newWexel;
+  }
+
+  before(async () => {
+    owner = anchor.web3.Keypair.generate();
+    await airdrop(owner.publicKey);
+
+    usdtMint = await createUsdtMint(provider);
+    ownerTokenAccount = await fundTokenAccount(provider, usdtMint, owner.publicKey, 100_000_000000);
+    poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+  });
+
+  it("should move the wexel record and NFT to the new owner", async () => {
+    const { wexel, wexelId, nftMint } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      owner.publicKey,
+      ownerTokenAccount,
+      [owner]
+    );
+    const recipient = anchor.web3.Keypair.generate();
+
+    const listener = program.addEventListener("wexelTransferred", (event) => {
+      expect(event.wexelId.toString()).to.equal(wexelId.toString());
+      expect(event.from.toBase58()).to.equal(owner.publicKey.toBase58());
+      expect(event.to.toBase58()).to.equal(recipient.publicKey.toBase58());
+    });
+    const newWexel = await transferWexel(wexel, wexelId, owner, recipient.publicKey);
+    await program.removeEventListener(listener);
+
+    // Old record is closed, the new one is indexed under the recipient
+    expect(await provider.connection.getAccountInfo(wexel)).to.be.null;
+    const moved = await program.account.wexel.fetch(newWexel);
+    expect(moved.id.toString()).to.equal(wexelId.toString());
+    expect(moved.owner.toBase58()).to.equal(recipient.publicKey.toBase58());
+    expect(moved.ownerIndex.toNumber()).to.equal(0);
+    expect(moved.principalUsd.toString()).to.equal("1000000000");
+    expect(moved.nftMint.toBase58()).to.equal(nftMint.toBase58());
+
+    const positions = await program.account.userPositions.fetch(userPositionsPda(program.programId, recipient.publicKey));
+    expect(positions.wexelCount.toNumber()).to.equal(1);
+
+    const recipientNft = await getAccount(
+      provider.connection,
+      wexelNftAccount(program.programId, wexel, recipient.publicKey),
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(recipientNft.amount.toString()).to.equal("1");
+  });
+
+  it("should refuse to transfer a collateralized wexel", async () => {
+    const { wexel, wexelId } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      owner.publicKey,
+      ownerTokenAccount,
+      [owner]
+    );
+    const { pool, poolVault } = poolPdas(program.programId, poolId);
+    const [collateralPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), wexelId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .collateralize(wexelId)
+      .accounts({
+        user: owner.publicKey,
+        wexel,
+        wexelNftAccount: wexelNftAccount(program.programId, wexel, owner.publicKey),
+        collateralPosition,
+        pool,
+        poolVault,
+        userTokenAccount: ownerTokenAccount,
+        wexelMint: wexelMintPda(program.programId, wexel),
+        config: configPda(program.programId),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    // The NFT itself is frozen while it backs the loan
+    const nft = await getAccount(
+      provider.connection,
+      wexelNftAccount(program.programId, wexel, owner.publicKey),
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(nft.isFrozen).to.be.true;
+
+    try {
+      await transferWexel(wexel, wexelId, owner, anchor.web3.Keypair.generate().publicKey);
+      expect.fail("Should have failed with WexelAlreadyCollateralized error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("WexelAlreadyCollateralized");
+    }
+  });
+
+  it("should only let the NFT holder transfer", async () => {
+    const { wexel, wexelId } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      owner.publicKey,
+      ownerTokenAccount,
+      [owner]
+    );
+    const stranger = anchor.web3.Keypair.generate();
+    await airdrop(stranger.publicKey);
+
+    try {
+      await transferWexel(wexel, wexelId, stranger, stranger.publicKey);
+      expect.fail("Should have failed because the signer does not hold the NFT");
+    } catch (error: any) {
+      expect(error).to.exist;
+    }
+  });
+});
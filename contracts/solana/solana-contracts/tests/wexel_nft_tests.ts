@@ -0,0 +1,187 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaContracts } from "../target/types/solana_contracts";
+import { expect } from "chai";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  getAccount,
+  getMint,
+  getTokenMetadata,
+  transferChecked,
+} from "@solana/spl-token";
+import {
+  applyBoost,
+  configPda,
+  createPool,
+  createUsdtMint,
+  deposit,
+  fundTokenAccount,
+  poolPdas,
+  setupBoostToken,
+  wexelMintPda,
+  wexelNftAccount,
+} from "./helpers";
+
+describe("Wexel NFT Tests", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.SolanaContracts as Program<SolanaContracts>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+
+  let user: anchor.web3.Keypair;
+  let usdtMint: anchor.web3.PublicKey;
+  let userTokenAccount: anchor.web3.PublicKey;
+  let poolId: anchor.BN;
+
+  before(async () => {
+    user = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(user.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+
+    usdtMint = await createUsdtMint(provider);
+    userTokenAccount = await fundTokenAccount(provider, usdtMint, user.publicKey, 100_000_000000);
+    poolId = new anchor.BN(Math.floor(Math.random() * 1000000));
+    await createPool(program, provider, poolId, usdtMint);
+  });
+
+  it("should mint a supply-1 Token-2022 NFT describing the wexel on deposit", async () => {
+    const { wexel, nftMint, nftAccount } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      user.publicKey,
+      userTokenAccount,
+      [user],
+      24
+    );
+
+    const mint = await getMint(provider.connection, nftMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(mint.supply.toString()).to.equal("1");
+    expect(mint.decimals).to.equal(0);
+    expect(mint.mintAuthority).to.be.null;
+
+    const holder = await getAccount(provider.connection, nftAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(holder.owner.toBase58()).to.equal(user.publicKey.toBase58());
+    expect(holder.amount.toString()).to.equal("1");
+
+    const wexelAccount = await program.account.wexel.fetch(wexel);
+    expect(wexelAccount.nftMint.toBase58()).to.equal(nftMint.toBase58());
+
+    const metadata = await getTokenMetadata(provider.connection, nftMint);
+    expect(metadata.name).to.equal(`Wexel #${wexelAccount.id.toString()}`);
+    expect(metadata.symbol).to.equal("WEXEL");
+    const fields = Object.fromEntries(metadata.additionalMetadata);
+    expect(fields).to.deep.equal({
+      principal_usd: "1000000000",
+      apy_bp: "3000",
+      apy_boost_bp: "0",
+      lock_period_months: "24",
+      matured_at: wexelAccount.maturedAt.toString(),
+    });
+  });
+
+  it("should refresh the NFT metadata when a boost is applied", async () => {
+    const { wexel, wexelId, nftMint } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      user.publicKey,
+      userTokenAccount,
+      [user]
+    );
+    const boost = await setupBoostToken(program, provider);
+    const userBoostTokenAccount = await fundTokenAccount(provider, boost.mint, user.publicKey, 300_000000);
+
+    await applyBoost(
+      program,
+      boost,
+      [boost.priceAccount.publicKey],
+      wexel,
+      wexelId,
+      new anchor.BN(300_000000),
+      user,
+      userBoostTokenAccount
+    );
+
+    const metadata = await getTokenMetadata(provider.connection, nftMint);
+    expect(Object.fromEntries(metadata.additionalMetadata).apy_boost_bp).to.equal("500");
+  });
+
+  it("should let whoever holds the NFT act on the wexel", async () => {
+    const { wexel, wexelId, nftMint, nftAccount } = await deposit(
+      program,
+      poolId,
+      new anchor.BN(1000_000000),
+      user.publicKey,
+      userTokenAccount,
+      [user]
+    );
+    const { pool, poolVault } = poolPdas(program.programId, poolId);
+    const [collateralPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), wexelId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // Hand the NFT to a new holder with a plain Token-2022 transfer
+    const buyer = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(buyer.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature);
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const buyerNftAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      nftMint,
+      buyer.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      provider.connection,
+      payer,
+      nftAccount,
+      nftMint,
+      buyerNftAccount,
+      user,
+      1,
+      0,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const buyerTokenAccount = await fundTokenAccount(provider, usdtMint, buyer.publicKey, 0);
+
+    const collateralize = (signer: anchor.web3.Keypair, tokenAccount: anchor.web3.PublicKey) =>
+      program.methods
+        .collateralize(wexelId)
+        .accounts({
+          user: signer.publicKey,
+          wexel,
+          wexelNftAccount: wexelNftAccount(program.programId, wexel, signer.publicKey),
+          collateralPosition,
+          pool,
+          poolVault,
+          userTokenAccount: tokenAccount,
+          wexelMint: wexelMintPda(program.programId, wexel),
+          config: configPda(program.programId),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+
+    // The depositor no longer holds the NFT
+    try {
+      await collateralize(user, userTokenAccount);
+      expect.fail("Should have failed because the depositor sold the NFT");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    await collateralize(buyer, buyerTokenAccount);
+    const loan = await getAccount(provider.connection, buyerTokenAccount);
+    expect(loan.amount.toString()).to.equal("600000000");
+  });
+});